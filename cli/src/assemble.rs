@@ -0,0 +1,173 @@
+use crate::{
+	payload::{PayloadFile, SignatureFile},
+	util, Address, Error,
+};
+use codec::Encode;
+use sp_runtime::{
+	generic::UncheckedExtrinsic,
+	traits::{IdentifyAccount, Verify},
+	MultiSignature,
+};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use substrate_airgapped::{DynamicExtra, GenericCall, RawArgs};
+
+/// Options for the `assemble` subcommand.
+#[derive(Debug, StructOpt)]
+pub struct Assemble {
+	/// Path to the payload file created by `construct`.
+	#[structopt(long, parse(from_os_str))]
+	payload: PathBuf,
+	/// Path to the signature file created by `sign`.
+	#[structopt(long, parse(from_os_str))]
+	signature: PathBuf,
+	/// File to write the hex of the extrinsic to. Defaults to stdout.
+	#[structopt(long, short, parse(from_os_str))]
+	output: Option<PathBuf>,
+}
+
+impl Assemble {
+	/// Assemble the extrinsic and write out its hex.
+	pub fn run(self) -> Result<(), Error> {
+		let payload_file: PayloadFile = util::read_json(&self.payload)?;
+		let signature_file: SignatureFile = util::read_json(&self.signature)?;
+		let extrinsic = assemble(&payload_file, &signature_file)?;
+
+		util::write_output(self.output.as_deref(), &util::encode_hex(&extrinsic.encode()))
+	}
+}
+
+/// Create a signed `UncheckedExtrinsic` from a payload and its signature, with the extra data
+/// exactly as in the payload. A payload file does not record its chain, so the extrinsic has the
+/// address and signature types the runtimes of all chains in the `Chain` registry share.
+///
+/// Errors if the signer is not the sender of the payload or if the signature is not valid for the
+/// payload.
+pub fn assemble(
+	payload_file: &PayloadFile,
+	signature_file: &SignatureFile,
+) -> Result<UncheckedExtrinsic<Address, GenericCall<RawArgs>, MultiSignature, DynamicExtra>, Error>
+{
+	let signer = signature_file.signer()?.into_account();
+	if signer != payload_file.sender()? {
+		return Err("Signer is not the sender of the payload".into());
+	}
+
+	let signature = signature_file.signature()?;
+	let payload = payload_file.signed_payload()?;
+	if !payload.using_encoded(|payload| signature.verify(payload, &signer)) {
+		return Err("Signature is not valid for the payload".into());
+	}
+
+	let (call, extra, _) = payload.deconstruct();
+
+	Ok(UncheckedExtrinsic::new_signed(call, signer.into(), signature, extra))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		construct::Construct, decode::tests::metadata_with_signed_extensions,
+		payload::tests::test_payload_file, sign::sign,
+	};
+	use sp_core::sr25519;
+	use sp_keyring::AccountKeyring;
+	use structopt::StructOpt;
+	use substrate_airgapped::{Chain, SigningBytes};
+
+	#[test]
+	fn assemble_creates_signed_extrinsic() {
+		let payload_file = test_payload_file();
+		let signature_file =
+			sign::<sr25519::Pair>("//Alice", &payload_file).expect("test case works");
+
+		let extrinsic = assemble(&payload_file, &signature_file).expect("test case works");
+		let extrinsic_encoded = extrinsic.encode();
+
+		let version_and_address = [
+			45u8, 2, 132, 0, 212, 53, 147, 199, 21, 253, 211, 28, 97, 20, 26, 189, 4, 169, 159,
+			214, 130, 44, 133, 88, 133, 76, 205, 227, 154, 86, 132, 231, 165, 109, 162, 125, 1,
+		];
+		assert_eq!(version_and_address, extrinsic_encoded[0..37]);
+		let (call, extra, _) =
+			payload_file.signed_payload().expect("test case works").deconstruct();
+		assert_eq!(extrinsic.function, call);
+		let extra_and_call = [payload_file.extra, payload_file.call]
+			.iter()
+			.map(|component| util::decode_hex(component).expect("test case works"))
+			.collect::<Vec<_>>()
+			.concat();
+		assert_eq!(extrinsic_encoded[101..], extra_and_call[..]);
+		assert_eq!(extrinsic.signature.map(|(_, _, extra)| extra), Some(extra));
+	}
+
+	#[test]
+	fn construct_sign_assemble_statemine_payload() {
+		let metadata = metadata_with_signed_extensions(&Chain::Statemine.signed_extensions());
+		let bob = util::encode_hex(AccountKeyring::Bob.to_account_id().as_ref());
+		let construct = Construct::from_iter(&[
+			"construct",
+			"--metadata",
+			"metadata.json",
+			"--pallet",
+			"Balances",
+			"--call",
+			"transfer",
+			"--args",
+			&format!("0x00{}30", bob.trim_start_matches("0x")),
+			"--sender",
+			&util::encode_hex(AccountKeyring::Alice.to_account_id().as_ref()),
+			"--nonce",
+			"7",
+			"--spec-version",
+			"1000000",
+			"--tx-version",
+			"10",
+			"--chain",
+			"statemine",
+			"--tip",
+			"1000",
+			"--fee-asset",
+			"1984",
+		]);
+		let payload_file = construct.payload_file(&metadata).expect("test case works");
+		// Nonce, tip and the `Some` fee asset of `ChargeAssetTxPayment`, then the disabled
+		// `CheckMetadataHash`
+		assert_eq!(payload_file.extra, "0x001ca10f01c007000000");
+
+		let signature_file =
+			sign::<sr25519::Pair>("//Alice", &payload_file).expect("test case works");
+		let extrinsic = assemble(&payload_file, &signature_file).expect("test case works");
+
+		let signature = signature_file.signature().expect("test case works");
+		let signing_bytes = SigningBytes::new(payload_file.bytes().expect("test case works"));
+		assert!(signature.verify(&signing_bytes.bytes[..], &AccountKeyring::Alice.to_account_id()));
+		let extrinsic_encoded = extrinsic.encode();
+		let extra_and_call = [payload_file.extra, payload_file.call]
+			.iter()
+			.map(|component| util::decode_hex(component).expect("test case works"))
+			.collect::<Vec<_>>()
+			.concat();
+		assert_eq!(extrinsic_encoded[101..], extra_and_call[..]);
+	}
+
+	#[test]
+	fn assemble_rejects_invalid_signature() {
+		let payload_file = test_payload_file();
+		let signature_file =
+			sign::<sr25519::Pair>("//Alice", &payload_file).expect("test case works");
+
+		// Payload with a nonce of 1, instead of the signed 0
+		let mut tampered_file = payload_file.clone();
+		tampered_file.extra = "0x000400".into();
+		assert!(assemble(&tampered_file, &signature_file).is_err());
+
+		// Signature of an account other than the sender
+		let bob_signature = SignatureFile::new(
+			AccountKeyring::Bob.public().as_ref(),
+			&MultiSignature::Sr25519(AccountKeyring::Bob.sign(&[])),
+		);
+		assert!(assemble(&payload_file, &bob_signature).is_err());
+	}
+}
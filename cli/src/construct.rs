@@ -0,0 +1,170 @@
+use crate::{payload::PayloadFile, util, Error};
+use metadata::{DynamicCall, Metadata};
+use sp_core::H256;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use substrate_airgapped::{
+	system::System, with_runtime, AssetId, Chain, GenericCall, MortalConfig, Mortality, RawArgs,
+	SupportedExtension, Tx, TxConfig,
+};
+
+/// Options for the `construct` subcommand.
+#[derive(Debug, StructOpt)]
+pub struct Construct {
+	/// Path to the runtime metadata, either as hex or as the JSON response to a
+	/// `state_getMetadata` RPC.
+	#[structopt(long, parse(from_os_str))]
+	metadata: PathBuf,
+	/// Name of the pallet the call is in, as it appears in metadata (e.g. `Balances`).
+	#[structopt(long)]
+	pallet: String,
+	/// Name of the call, as it appears in metadata (e.g. `transfer`).
+	#[structopt(long)]
+	call: String,
+	/// Hex of the SCALE encoded call arguments. Defaults to no arguments.
+	#[structopt(long)]
+	args: Option<String>,
+	/// JSON array of the values of the call arguments, e.g. `[{"Id": "5FHneW46..."}, 12]`, to be
+	/// encoded using the type registry of the metadata (V14 onwards). Used instead of `--args`.
+	#[structopt(long, conflicts_with = "args")]
+	values: Option<String>,
+	/// Address of the signer, as hex or as an SS58 address of the chain.
+	#[structopt(long)]
+	sender: String,
+	/// Nonce of the signer.
+	#[structopt(long)]
+	nonce: u32,
+	/// API specification version of the runtime.
+	#[structopt(long)]
+	spec_version: u32,
+	/// Transaction version of the runtime.
+	#[structopt(long)]
+	tx_version: u32,
+	/// Name of the chain (e.g. `polkadot`). Detected from `--genesis-hash` if not given.
+	#[structopt(long, required_unless = "genesis-hash")]
+	chain: Option<Chain>,
+	/// Hash of the network's genesis block. Defaults to the genesis hash of `--chain`; it is an
+	/// error if the two do not match.
+	#[structopt(long, parse(try_from_str = util::parse_h256))]
+	genesis_hash: Option<H256>,
+	/// Number of blocks, starting at the checkpoint block, the transaction is valid for. The
+	/// transaction is immortal if this is not given.
+	#[structopt(long, requires_all = &["checkpoint-number", "checkpoint-hash"])]
+	mortal_period: Option<u64>,
+	/// Number of the block where the transaction's mortality period starts.
+	#[structopt(long, requires = "mortal-period")]
+	checkpoint_number: Option<u64>,
+	/// Hash of the block where the transaction's mortality period starts.
+	#[structopt(long, requires = "mortal-period", parse(try_from_str = util::parse_h256))]
+	checkpoint_hash: Option<H256>,
+	/// Tip, used for transaction priority.
+	#[structopt(long, default_value = "0")]
+	tip: u128,
+	/// Asset to pay the fees in, as a `u32` asset id or the hex of the SCALE encoded asset id of
+	/// the runtime. Requires the `ChargeAssetTxPayment` signed extension, e.g. of Statemine.
+	#[structopt(long, parse(try_from_str = util::parse_asset_id))]
+	fee_asset: Option<AssetId>,
+	/// Output the hex of the SCALE encoded `SignedPayload` instead of a payload file.
+	#[structopt(long)]
+	hex: bool,
+	/// File to write the output to. Defaults to stdout.
+	#[structopt(long, short, parse(from_os_str))]
+	output: Option<PathBuf>,
+}
+
+impl Construct {
+	/// Construct the `SignedPayload` and write it out.
+	pub fn run(self) -> Result<(), Error> {
+		let metadata = util::read_metadata(&self.metadata)?;
+		let payload_file = self.payload_file(&metadata)?;
+		let output = if self.hex {
+			util::encode_hex(&payload_file.bytes()?)
+		} else {
+			serde_json::to_string_pretty(&payload_file)?
+		};
+
+		util::write_output(self.output.as_deref(), &output)
+	}
+
+	/// Construct the `SignedPayload` of the call in `metadata`.
+	pub fn payload_file(&self, metadata: &Metadata) -> Result<PayloadFile, Error> {
+		let (chain, genesis_hash) = match (self.chain, self.genesis_hash) {
+			(Some(chain), genesis_hash) => {
+				(chain, genesis_hash.unwrap_or_else(|| chain.genesis_hash()))
+			}
+			(None, Some(genesis_hash)) => {
+				(util::chain_from_genesis_hash(&genesis_hash)?, genesis_hash)
+			}
+			(None, None) => return Err("Either --chain or --genesis-hash is required".into()),
+		};
+
+		let call = match &self.values {
+			Some(values) => {
+				let values = match serde_json::from_str(values)? {
+					serde_json::Value::Array(values) => values,
+					_ => return Err("Expected a JSON array of argument values".into()),
+				};
+				let arguments =
+					values.into_iter().map(util::json_to_value).collect::<Result<_, _>>()?;
+				DynamicCall::new(&self.pallet, &self.call, arguments)
+					.with_ss58_format(chain.ss58_format())
+					.to_generic_call(metadata)?
+			}
+			None => {
+				let call_index = metadata.call_index(&self.pallet, &self.call)?;
+				let args = util::decode_hex(self.args.as_deref().unwrap_or("0x"))?;
+				GenericCall::new(call_index, RawArgs(args))
+			}
+		};
+
+		let unsupported: Vec<_> = metadata
+			.signed_extensions()
+			.iter()
+			.filter(|extension| SupportedExtension::from_identifier(extension).is_none())
+			.map(String::as_str)
+			.collect();
+		if !unsupported.is_empty() {
+			return Err(format!("Unsupported signed extensions: {}", unsupported.join(", ")).into());
+		}
+
+		let sender = util::parse_account(&self.sender, chain)?;
+		let payload_file = with_runtime!(chain, R => {
+			let tx: Tx<RawArgs, R> = Tx::new(TxConfig {
+				call,
+				address: sender.clone().into(),
+				nonce: self.nonce,
+				tx_version: self.tx_version,
+				spec_version: self.spec_version,
+				genesis_hash,
+				mortality: self.mortality(),
+				tip: self.tip,
+				fee_asset: self.fee_asset.clone(),
+				metadata_hash: None,
+			})?;
+			// Use the signed extensions of the metadata where it lists them, so a runtime with
+			// other extensions than the ones of `R` fails instead of producing an invalid payload
+			match metadata.signed_extensions() {
+				[] => PayloadFile::new(&sender, tx.signed_payload()?),
+				signed_extensions => {
+					PayloadFile::new(&sender, tx.dynamic_signed_payload(signed_extensions)?)
+				}
+			}
+		});
+
+		Ok(payload_file)
+	}
+
+	/// Mortality of the transaction, for runtime `R`.
+	fn mortality<R: System<Hash = H256>>(&self) -> Mortality<R> {
+		match (self.mortal_period, self.checkpoint_number, self.checkpoint_hash) {
+			(Some(period), Some(checkpoint_block_number), Some(checkpoint_block_hash)) => {
+				Mortality::Mortal(MortalConfig {
+					period,
+					checkpoint_block_number,
+					checkpoint_block_hash,
+				})
+			}
+			_ => Mortality::Immortal,
+		}
+	}
+}
@@ -0,0 +1,948 @@
+use crate::{payload::PayloadFile, util, Address, Error};
+use codec::{Compact, Decode as _};
+use metadata::{DecodedCall, Fields, Metadata, Value};
+use sp_core::{
+	crypto::{Ss58AddressFormat, Ss58Codec},
+	H256,
+};
+use sp_runtime::{generic::Era, traits::SignedExtension, MultiSignature};
+use std::{fs, path::PathBuf};
+use structopt::StructOpt;
+use substrate_airgapped::{
+	check_extrinsic, system::System, with_runtime, Chain, DynamicExtra, ExtrinsicChecks,
+	MultiAddress, Runtime, SupportedExtension,
+};
+
+/// Options for the `decode` subcommand.
+#[derive(Debug, StructOpt)]
+pub struct Decode {
+	/// Hex of the SCALE encoded `SignedPayload` or `UncheckedExtrinsic`, or the path to a file
+	/// containing it. A payload file created by `construct` is also accepted.
+	input: String,
+	/// Path to the runtime metadata, either as hex or as the JSON response to a
+	/// `state_getMetadata` RPC.
+	#[structopt(long, parse(from_os_str))]
+	metadata: PathBuf,
+	/// Name of the chain the input is expected to be for (e.g. `polkadot`). The chain of a payload
+	/// is detected from its genesis hash, and it is an error if that is not the given chain's.
+	#[structopt(long)]
+	chain: Option<Chain>,
+	/// Decode the input as a signed `UncheckedExtrinsic` instead of a `SignedPayload`.
+	#[structopt(long)]
+	extrinsic: bool,
+	/// Number of a block within the validity period of a mortal transaction (e.g. its checkpoint
+	/// block), used to show the first and last block the transaction is valid in.
+	#[structopt(long)]
+	block_number: Option<u64>,
+	/// Verify the signature of the extrinsic, rebuilding the signed payload with the genesis hash
+	/// of `--chain`, `--spec-version`, `--tx-version` and `--checkpoint-hash`.
+	#[structopt(long, requires_all = &["extrinsic", "chain", "spec-version", "tx-version"])]
+	verify: bool,
+	/// Spec version of the runtime the extrinsic was signed for, to verify it.
+	#[structopt(long)]
+	spec_version: Option<u32>,
+	/// Transaction version of the runtime the extrinsic was signed for, to verify it.
+	#[structopt(long)]
+	tx_version: Option<u32>,
+	/// Hash of the checkpoint block of a mortal extrinsic, to verify it.
+	#[structopt(long, parse(try_from_str = util::parse_h256))]
+	checkpoint_hash: Option<H256>,
+	/// Metadata hash of an extrinsic signed with `CheckMetadataHash` enabled, to verify it.
+	#[structopt(long, parse(try_from_str = util::parse_h256))]
+	metadata_hash: Option<H256>,
+}
+
+impl Decode {
+	/// Decode the input and print it.
+	pub fn run(self) -> Result<(), Error> {
+		let metadata = util::read_metadata(&self.metadata)?;
+		let bytes = self.input_bytes()?;
+
+		let (mut decoded, extrinsic) = if self.extrinsic {
+			let extrinsic = decode_extrinsic(&metadata, self.chain, &bytes)?;
+			(extrinsic.decoded.clone(), Some(extrinsic))
+		} else {
+			(decode_payload(&metadata, self.chain, &bytes)?, None)
+		};
+		match (self.chain, &decoded.additional_signed) {
+			(Some(chain), Some(additional_signed)) => {
+				chain.check_genesis_hash(additional_signed.genesis_hash.as_bytes())?
+			}
+			(Some(chain), None) => decoded.chain = Some(chain),
+			(None, _) => {}
+		}
+		print!("{}", decoded.display(self.block_number));
+
+		if self.verify {
+			let extrinsic = extrinsic.ok_or("--verify requires --extrinsic")?;
+			let chain = self.chain.ok_or("--verify requires --chain")?;
+			let (spec_version, tx_version) = match (self.spec_version, self.tx_version) {
+				(Some(spec_version), Some(tx_version)) => (spec_version, tx_version),
+				_ => return Err("--verify requires --spec-version and --tx-version".into()),
+			};
+			let checks = verify(
+				&extrinsic,
+				chain,
+				spec_version,
+				tx_version,
+				self.checkpoint_hash,
+				self.metadata_hash.map(H256::to_fixed_bytes),
+			)?;
+			print!("{}", display_checks(&checks));
+			if !checks.all_passed() {
+				return Err("Extrinsic failed verification".into());
+			}
+		}
+
+		Ok(())
+	}
+
+	fn input_bytes(&self) -> Result<Vec<u8>, Error> {
+		if self.input.starts_with("0x") {
+			return util::decode_hex(&self.input);
+		}
+
+		let contents = fs::read_to_string(&self.input)?;
+		if contents.trim_start().starts_with('{') {
+			serde_json::from_str::<PayloadFile>(&contents)?.bytes()
+		} else {
+			util::decode_hex(&contents)
+		}
+	}
+}
+
+/// Verify the signature of a decoded `UncheckedExtrinsic` for `chain`, rebuilding its
+/// `SignedPayload` from the bytes of its call and extra and the given additional signed data.
+pub fn verify(
+	extrinsic: &DecodedExtrinsic,
+	chain: Chain,
+	spec_version: u32,
+	tx_version: u32,
+	checkpoint_hash: Option<H256>,
+	metadata_hash: Option<[u8; 32]>,
+) -> Result<ExtrinsicChecks, Error> {
+	let extra = &extrinsic.decoded.extra;
+	let checks = with_runtime!(chain, R => {
+		let additional_signed = DynamicExtra::new::<R, _>(
+			&extrinsic.signed_extensions,
+			spec_version,
+			tx_version,
+			extra.nonce,
+			chain.genesis_hash(),
+			(extra.era, checkpoint_hash),
+			extra.tip,
+			None,
+			metadata_hash,
+		)?
+		.additional_signed()
+		.map_err(substrate_airgapped::Error::from)?;
+
+		check_extrinsic::<R>(
+			extrinsic.bytes,
+			&extrinsic.signer,
+			&extrinsic.signature,
+			&chain.genesis_hash(),
+			[extrinsic.call, extrinsic.extra, &additional_signed.0].concat(),
+		)?
+	});
+
+	Ok(checks)
+}
+
+/// Show the outcome of each check of verifying an extrinsic. The genesis hash is not shown, as the
+/// payload is rebuilt with the genesis hash of the chain.
+pub fn display_checks(checks: &ExtrinsicChecks) -> String {
+	let outcome = |passed: bool| if passed { "ok" } else { "FAILED" };
+	format!(
+		"Length: {}\nVersion: {}\nSigner account: {}\nSignature: {}\n",
+		outcome(checks.length),
+		outcome(checks.version),
+		outcome(checks.signer_account),
+		outcome(checks.signature),
+	)
+}
+
+/// Bit of the version byte set for signed extrinsics.
+const SIGNED_BIT: u8 = 0b1000_0000;
+
+/// Human readable components of a `SignedPayload` or `UncheckedExtrinsic`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decoded {
+	/// Chain the transaction is for, if known. Accounts are shown as SS58 addresses of the chain.
+	pub chain: Option<Chain>,
+	/// The call.
+	pub call: DecodedCall,
+	/// Signer of an extrinsic.
+	pub signer: Option<Address>,
+	/// Extra data of the signed extensions.
+	pub extra: Extra,
+	/// Additional signed data, only part of a `SignedPayload`.
+	pub additional_signed: Option<AdditionalSigned>,
+}
+
+/// Extra data of the signed extensions of a transaction. Values of extensions the runtime does
+/// not have are left at their default.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Extra {
+	/// Mortality of the transaction, of `CheckEra` or `CheckMortality`.
+	pub era: Era,
+	/// Nonce of the signer, of `CheckNonce`.
+	pub nonce: u32,
+	/// Tip, used for transaction priority, of `ChargeTransactionPayment` or
+	/// `ChargeAssetTxPayment`.
+	pub tip: u128,
+	/// Asset the fees are paid in, of `ChargeAssetTxPayment`, `None` for the native token.
+	pub fee_asset: Option<Value>,
+	/// Whether the metadata hash is checked, of `CheckMetadataHash`.
+	pub metadata_hash_enabled: bool,
+}
+
+impl Default for Extra {
+	fn default() -> Self {
+		Extra {
+			era: Era::Immortal,
+			nonce: 0,
+			tip: 0,
+			fee_asset: None,
+			metadata_hash_enabled: false,
+		}
+	}
+}
+
+/// Additional signed data of a `SignedPayload`. Values of extensions the runtime does not have
+/// are left at their default.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AdditionalSigned {
+	/// API specification version of the runtime.
+	pub spec_version: u32,
+	/// Transaction version of the runtime.
+	pub tx_version: u32,
+	/// Hash of the network's genesis block.
+	pub genesis_hash: H256,
+	/// Hash of the block the mortality period starts at, or the genesis hash if immortal.
+	pub checkpoint_hash: H256,
+	/// RFC-78 hash of the metadata, if checked by `CheckMetadataHash`.
+	pub metadata_hash: Option<[u8; 32]>,
+}
+
+/// A decoded signed `UncheckedExtrinsic`, with its bytes and the bytes of its call and extra to
+/// verify its signature.
+#[derive(Debug)]
+pub struct DecodedExtrinsic<'a> {
+	/// Human readable components.
+	pub decoded: Decoded,
+	bytes: &'a [u8],
+	signer: Address,
+	signature: MultiSignature,
+	signed_extensions: Vec<String>,
+	extra: &'a [u8],
+	call: &'a [u8],
+}
+
+/// Decode the signer and signature of an extrinsic with the runtime of `chain`, or with the types
+/// the runtimes of all chains in the `Chain` registry share if the chain is not known.
+fn decode_signer(
+	chain: Option<Chain>,
+	input: &mut &[u8],
+) -> Result<(Address, MultiSignature), Error> {
+	Ok(match chain {
+		Some(chain) => with_runtime!(chain, R => (
+			<R as System>::Address::decode(input)?,
+			<R as Runtime>::Signature::decode(input)?,
+		)),
+		None => (Address::decode(input)?, MultiSignature::decode(input)?),
+	})
+}
+
+/// Decode a `SignedPayload`.
+///
+/// The extra and additional signed data are decoded by the signed extensions of the metadata, or
+/// of `chain` if the metadata does not list them.
+pub fn decode_payload(
+	metadata: &Metadata,
+	chain: Option<Chain>,
+	bytes: &[u8],
+) -> Result<Decoded, Error> {
+	let signed_extensions = signed_extensions(metadata, chain)?;
+	let mut input = bytes;
+	let call = metadata.decode_call(&mut input)?;
+	let extra = decode_extra(metadata, &signed_extensions, &mut input)?;
+	let additional_signed = decode_additional_signed(&signed_extensions, &mut input)?;
+	if !input.is_empty() {
+		return Err("Payload has trailing bytes".into());
+	}
+
+	Ok(Decoded {
+		chain: Chain::from_genesis_hash(additional_signed.genesis_hash.as_bytes()),
+		call,
+		signer: None,
+		extra,
+		additional_signed: Some(additional_signed),
+	})
+}
+
+/// Decode a signed `UncheckedExtrinsic`.
+///
+/// The extra data is decoded by the signed extensions of the metadata, or of `chain` if the
+/// metadata does not list them. The asset id of `ChargeAssetTxPayment` is decoded with the type
+/// registry of V14 metadata onwards, as it has a runtime specific type.
+pub fn decode_extrinsic<'a>(
+	metadata: &Metadata,
+	chain: Option<Chain>,
+	bytes: &'a [u8],
+) -> Result<DecodedExtrinsic<'a>, Error> {
+	let signed_extensions = signed_extensions(metadata, chain)?;
+	let mut input = bytes;
+	Compact::<u32>::decode(&mut input)?;
+	let version = u8::decode(&mut input)?;
+	if version & SIGNED_BIT == 0 {
+		return Err("Extrinsic is not signed".into());
+	}
+	let (signer, signature) = decode_signer(chain, &mut input)?;
+
+	let extra_bytes = input;
+	let extra = decode_extra(metadata, &signed_extensions, &mut input)?;
+	let extra_bytes = &extra_bytes[..extra_bytes.len() - input.len()];
+	let call_bytes = input;
+	let call = metadata.decode_call(&mut input)?;
+	if !input.is_empty() {
+		return Err("Call has trailing bytes".into());
+	}
+
+	Ok(DecodedExtrinsic {
+		decoded: Decoded {
+			chain: None,
+			call,
+			signer: Some(signer.clone()),
+			extra,
+			additional_signed: None,
+		},
+		bytes,
+		signer,
+		signature,
+		signed_extensions,
+		extra: extra_bytes,
+		call: call_bytes,
+	})
+}
+
+/// Identifiers of the signed extensions of the metadata, or of `chain` if the metadata does not
+/// list them, e.g. before V11.
+fn signed_extensions(metadata: &Metadata, chain: Option<Chain>) -> Result<Vec<String>, Error> {
+	match (metadata.signed_extensions(), chain) {
+		([], Some(chain)) => {
+			Ok(chain.signed_extensions().iter().map(|&id| id.to_owned()).collect())
+		}
+		([], None) => Err("Metadata does not list signed extensions, --chain is required".into()),
+		(signed_extensions, _) => Ok(signed_extensions.to_vec()),
+	}
+}
+
+/// Decode the extra data of `signed_extensions`, in their order.
+fn decode_extra(
+	metadata: &Metadata,
+	signed_extensions: &[String],
+	input: &mut &[u8],
+) -> Result<Extra, Error> {
+	use SupportedExtension::*;
+
+	let mut extra = Extra::default();
+	for identifier in signed_extensions {
+		match supported_extension(identifier)? {
+			CheckEra | CheckMortality => extra.era = Era::decode(input)?,
+			CheckNonce => extra.nonce = Compact::<u32>::decode(input)?.0,
+			ChargeTransactionPayment => extra.tip = Compact::<u128>::decode(input)?.0,
+			ChargeAssetTxPayment => {
+				let mut native = *input;
+				extra.tip = Compact::<u128>::decode(&mut native)?.0;
+				if u8::decode(&mut native)? == 0 {
+					*input = native;
+				} else {
+					// The type of the asset id is only known from the type registry
+					let extension = metadata.decode_signed_extension(identifier, input)?;
+					extra.fee_asset = Some(fee_asset(extension)?);
+				}
+			}
+			CheckMetadataHash => {
+				extra.metadata_hash_enabled = match u8::decode(input)? {
+					0 => false,
+					1 => true,
+					_ => return Err("Invalid metadata hash mode".into()),
+				}
+			}
+			CheckSpecVersion | CheckTxVersion | CheckGenesis | CheckWeight | PrevalidateAttests
+			| CheckNonZeroSender => {}
+		}
+	}
+
+	Ok(extra)
+}
+
+/// The asset id of `ChargeAssetTxPayment` decoded with the type registry.
+fn fee_asset(extension: Value) -> Result<Value, Error> {
+	let fields = match extension {
+		Value::Composite(Fields::Named(fields)) => fields,
+		_ => return Err("Unexpected type of ChargeAssetTxPayment".into()),
+	};
+	match fields.into_iter().find(|(name, _)| name == "asset_id") {
+		Some((_, Value::Variant(variant, Fields::Unnamed(mut values))))
+			if variant == "Some" && values.len() == 1 =>
+		{
+			Ok(values.remove(0))
+		}
+		_ => Err("Unexpected type of ChargeAssetTxPayment".into()),
+	}
+}
+
+/// Decode the additional signed data of `signed_extensions`, in their order.
+fn decode_additional_signed(
+	signed_extensions: &[String],
+	input: &mut &[u8],
+) -> Result<AdditionalSigned, Error> {
+	use SupportedExtension::*;
+
+	let mut additional_signed = AdditionalSigned::default();
+	for identifier in signed_extensions {
+		match supported_extension(identifier)? {
+			CheckSpecVersion => additional_signed.spec_version = u32::decode(input)?,
+			CheckTxVersion => additional_signed.tx_version = u32::decode(input)?,
+			CheckGenesis => additional_signed.genesis_hash = H256::decode(input)?,
+			CheckEra | CheckMortality => additional_signed.checkpoint_hash = H256::decode(input)?,
+			CheckMetadataHash => additional_signed.metadata_hash = Option::decode(input)?,
+			CheckNonce
+			| CheckWeight
+			| ChargeTransactionPayment
+			| ChargeAssetTxPayment
+			| PrevalidateAttests
+			| CheckNonZeroSender => {}
+		}
+	}
+
+	Ok(additional_signed)
+}
+
+/// The `SupportedExtension` with `identifier`.
+fn supported_extension(identifier: &str) -> Result<SupportedExtension, Error> {
+	SupportedExtension::from_identifier(identifier)
+		.ok_or_else(|| format!("Unsupported signed extension {}", identifier).into())
+}
+
+impl Decoded {
+	/// Human readable representation. `block_number` is a block within the validity period
+	/// of the transaction, used to show the period's first and last block.
+	pub fn display(&self, block_number: Option<u64>) -> String {
+		let format = self.chain.map(|chain| chain.ss58_format());
+		let mut output = String::new();
+		if let Some(chain) = self.chain {
+			output += &format!("Chain: {}\n", chain);
+		}
+		let display_value = |value: &Value| match format {
+			Some(format) => value.display_with_ss58_format(format).to_string(),
+			None => value.to_string(),
+		};
+		output += &format!("Call: {}.{}\n", self.call.pallet, self.call.call);
+		for (name, value) in &self.call.arguments {
+			output += &format!("  {}: {}\n", name, display_value(value));
+		}
+		if let Some(signer) = &self.signer {
+			output += &format!("Signer: {}\n", format_address(signer, format));
+		}
+
+		let extra = &self.extra;
+		output += &format!("Nonce: {}\n", extra.nonce);
+		output += &match self.chain {
+			Some(chain) => {
+				format!("Tip: {} ({})\n", extra.tip, util::format_balance(extra.tip, chain))
+			}
+			None => format!("Tip: {}\n", extra.tip),
+		};
+		if let Some(fee_asset) = &extra.fee_asset {
+			output += &format!("Fee asset: {}\n", display_value(fee_asset));
+		}
+		output += &match extra.era {
+			Era::Immortal => "Era: Immortal\n".to_owned(),
+			Era::Mortal(period, phase) => {
+				let window = match block_number {
+					Some(number) => format!(
+						", valid from block #{} to #{}",
+						extra.era.birth(number),
+						extra.era.death(number) - 1
+					),
+					None => String::new(),
+				};
+				format!("Era: Mortal (period: {}, phase: {}){}\n", period, phase, window)
+			}
+		};
+		if extra.metadata_hash_enabled {
+			output += "Metadata hash check: enabled\n";
+		}
+
+		if let Some(additional_signed) = &self.additional_signed {
+			output += &format!(
+				"Spec version: {}\nTx version: {}\nGenesis hash: {}\nCheckpoint hash: {}\n",
+				additional_signed.spec_version,
+				additional_signed.tx_version,
+				util::encode_hex(additional_signed.genesis_hash.as_ref()),
+				util::encode_hex(additional_signed.checkpoint_hash.as_ref()),
+			);
+			if let Some(metadata_hash) = &additional_signed.metadata_hash {
+				output += &format!("Metadata hash: {}\n", util::encode_hex(metadata_hash));
+			}
+		}
+
+		output
+	}
+}
+
+/// Format an address, with an account as an SS58 address with the network prefix `format`, or
+/// the default of `sp_core`.
+fn format_address(address: &Address, format: Option<Ss58AddressFormat>) -> String {
+	match (address, format) {
+		(MultiAddress::Id(account), Some(format)) => account.to_ss58check_with_version(format),
+		(address, _) => address.to_string(),
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use crate::{
+		assemble::assemble, construct::Construct, payload::tests::test_payload_file, sign::sign,
+	};
+	use codec::Encode;
+	use core::convert::TryInto;
+	use frame_metadata::{
+		decode_different::DecodeDifferent,
+		v12::{
+			ExtrinsicMetadata, FunctionArgumentMetadata, FunctionMetadata, ModuleMetadata,
+			RuntimeMetadataV12,
+		},
+		v14, META_RESERVED,
+	};
+	use metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+	use scale_info::{meta_type, TypeInfo};
+	use sp_core::sr25519;
+	use sp_keyring::AccountKeyring;
+	use structopt::StructOpt;
+	use substrate_airgapped::{
+		CallIndex, GenericCall, KusamaRuntime, MortalConfig, Mortality, RawArgs, Tx, TxConfig,
+	};
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	struct AccountId32([u8; 32]);
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	enum MultiAddress {
+		Id(AccountId32),
+		Index(#[codec(compact)] u32),
+	}
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code, non_camel_case_types)]
+	enum BalancesCall {
+		#[codec(index = 3)]
+		transfer_keep_alive {
+			dest: MultiAddress,
+			#[codec(compact)]
+			value: u128,
+		},
+	}
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	struct ChargeAssetTxPayment {
+		#[codec(compact)]
+		tip: u128,
+		asset_id: Option<u32>,
+	}
+
+	fn module(name: &str, index: u8, calls: Vec<(&str, Vec<(&str, &str)>)>) -> ModuleMetadata {
+		let calls = calls
+			.into_iter()
+			.map(|(name, arguments)| FunctionMetadata {
+				name: DecodeDifferent::Decoded(name.to_owned()),
+				arguments: DecodeDifferent::Decoded(
+					arguments
+						.into_iter()
+						.map(|(name, ty)| FunctionArgumentMetadata {
+							name: DecodeDifferent::Decoded(name.to_owned()),
+							ty: DecodeDifferent::Decoded(ty.to_owned()),
+						})
+						.collect(),
+				),
+				documentation: DecodeDifferent::Decoded(vec![]),
+			})
+			.collect();
+
+		ModuleMetadata {
+			name: DecodeDifferent::Decoded(name.to_owned()),
+			storage: None,
+			calls: Some(DecodeDifferent::Decoded(calls)),
+			event: None,
+			constants: DecodeDifferent::Decoded(vec![]),
+			errors: DecodeDifferent::Decoded(vec![]),
+			index,
+		}
+	}
+
+	/// Construct V12 `Metadata` with a subset of the calls of a Kusama like runtime and the given
+	/// signed extensions.
+	pub(crate) fn metadata_with_signed_extensions(signed_extensions: &[&str]) -> Metadata {
+		let modules = vec![
+			module("System", 0, vec![("remark", vec![("_remark", "Vec<u8>")])]),
+			module(
+				"Balances",
+				5,
+				vec![(
+					"transfer",
+					vec![
+						("dest", "<T::Lookup as StaticLookup>::Source"),
+						("value", "Compact<T::Balance>"),
+					],
+				)],
+			),
+			module(
+				"Utility",
+				24,
+				vec![
+					("batch", vec![("calls", "Vec<<T as Trait>::Call>")]),
+					("as_derivative", vec![("index", "u16"), ("call", "Box<<T as Trait>::Call>")]),
+					("unsupported", vec![("weight", "Weight")]),
+				],
+			),
+		];
+		let metadata = RuntimeMetadataPrefixed(
+			META_RESERVED,
+			RuntimeMetadata::V12(RuntimeMetadataV12 {
+				modules: DecodeDifferent::Decoded(modules),
+				extrinsic: ExtrinsicMetadata {
+					version: 4,
+					signed_extensions: signed_extensions
+						.iter()
+						.map(|identifier| DecodeDifferent::Decoded((*identifier).to_owned()))
+						.collect(),
+				},
+			}),
+		);
+
+		metadata.try_into().expect("test case works")
+	}
+
+	/// Construct V14 `Metadata` with a transfer call and the signed extensions of Statemine, where
+	/// the asset id to pay fees in is a `u32`.
+	fn statemine_metadata() -> Metadata {
+		let balances = v14::PalletMetadata {
+			name: "Balances",
+			storage: None,
+			calls: Some(v14::PalletCallMetadata { ty: meta_type::<BalancesCall>() }),
+			event: None,
+			constants: vec![],
+			error: None,
+			index: 10,
+		};
+		let signed_extensions = Chain::Statemine
+			.signed_extensions()
+			.iter()
+			.map(|identifier| v14::SignedExtensionMetadata {
+				identifier: *identifier,
+				ty: match *identifier {
+					"ChargeAssetTxPayment" => meta_type::<ChargeAssetTxPayment>(),
+					_ => meta_type::<()>(),
+				},
+				additional_signed: meta_type::<()>(),
+			})
+			.collect();
+		let extrinsic =
+			v14::ExtrinsicMetadata { ty: meta_type::<()>(), version: 4, signed_extensions };
+		let metadata: RuntimeMetadataPrefixed =
+			v14::RuntimeMetadataV14::new(vec![balances], extrinsic, meta_type::<()>()).into();
+
+		metadata.try_into().expect("test case works")
+	}
+
+	/// Construct V12 `Metadata` with a subset of the calls and the signed extensions of a Kusama
+	/// like runtime.
+	fn test_metadata() -> Metadata {
+		metadata_with_signed_extensions(&Chain::Kusama.signed_extensions())
+	}
+
+	fn transfer_to_bob() -> DecodedCall {
+		let bob = Value::Account(AccountKeyring::Bob.to_account_id());
+		DecodedCall {
+			pallet: "Balances".into(),
+			call: "transfer".into(),
+			arguments: vec![
+				("dest".into(), Value::Variant("Id".into(), Fields::Unnamed(vec![bob]))),
+				("value".into(), Value::Compact(Box::new(Value::UInt(12)))),
+			],
+		}
+	}
+
+	#[test]
+	fn decode_payload_works() {
+		let payload = test_payload_file().bytes().expect("test case works");
+		let decoded = decode_payload(&test_metadata(), None, &payload).expect("test case works");
+
+		assert_eq!(
+			decoded,
+			Decoded {
+				chain: Some(Chain::Kusama),
+				call: transfer_to_bob(),
+				signer: None,
+				extra: Extra::default(),
+				additional_signed: Some(AdditionalSigned {
+					spec_version: 26,
+					tx_version: 4,
+					genesis_hash: KusamaRuntime::GENESIS_HASH,
+					checkpoint_hash: KusamaRuntime::GENESIS_HASH,
+					metadata_hash: None,
+				}),
+			}
+		);
+		// Accounts are shown as SS58 addresses of the chain
+		let bob = Chain::Kusama.format_account(&AccountKeyring::Bob.to_account_id());
+		assert!(decoded.display(None).starts_with(&format!(
+			"Chain: Kusama\nCall: Balances.transfer\n  dest: Id({})\n",
+			bob
+		)));
+	}
+
+	#[test]
+	fn decode_payload_decodes_the_signed_extensions_of_the_metadata() {
+		let signed_extensions = [
+			"CheckSpecVersion",
+			"CheckTxVersion",
+			"CheckGenesis",
+			"CheckEra",
+			"CheckNonce",
+			"CheckWeight",
+			"ChargeAssetTxPayment",
+			"CheckMetadataHash",
+		];
+		let mut args = Address::from(AccountKeyring::Bob.to_account_id()).encode();
+		args.push(48);
+		let checkpoint_hash = H256::repeat_byte(1);
+		let tx: Tx<RawArgs, KusamaRuntime> = Tx::new(TxConfig {
+			call: GenericCall::new(CallIndex::new(5, 0), RawArgs(args)),
+			address: AccountKeyring::Alice.to_account_id().into(),
+			nonce: 7,
+			tx_version: 4,
+			spec_version: 26,
+			genesis_hash: KusamaRuntime::GENESIS_HASH,
+			mortality: Mortality::Mortal(MortalConfig {
+				period: 64,
+				checkpoint_block_number: 100,
+				checkpoint_block_hash: checkpoint_hash,
+			}),
+			tip: 1_000,
+			fee_asset: None,
+			metadata_hash: Some([2; 32]),
+		})
+		.expect("test case works");
+		let payload = tx.dynamic_signed_payload(&signed_extensions).expect("test case works");
+		let metadata = metadata_with_signed_extensions(&signed_extensions);
+
+		let decoded = decode_payload(&metadata, None, &payload.encode()).expect("test case works");
+		assert_eq!(
+			decoded.extra,
+			Extra {
+				era: Era::mortal(64, 100),
+				nonce: 7,
+				tip: 1_000,
+				fee_asset: None,
+				metadata_hash_enabled: true,
+			}
+		);
+		assert_eq!(
+			decoded.additional_signed,
+			Some(AdditionalSigned {
+				spec_version: 26,
+				tx_version: 4,
+				genesis_hash: KusamaRuntime::GENESIS_HASH,
+				checkpoint_hash,
+				metadata_hash: Some([2; 32]),
+			})
+		);
+		assert!(decoded.display(None).contains("Metadata hash check: enabled\n"));
+
+		// Metadata that does not list the signed extensions requires a chain to take them from
+		let payload = test_payload_file().bytes().expect("test case works");
+		let metadata = metadata_with_signed_extensions(&[]);
+		assert!(decode_payload(&metadata, None, &payload).is_err());
+		let decoded =
+			decode_payload(&metadata, Some(Chain::Kusama), &payload).expect("test case works");
+		assert_eq!(decoded.call, transfer_to_bob());
+	}
+
+	#[test]
+	fn decode_extrinsic_works() {
+		let payload_file = test_payload_file();
+		let signature_file =
+			sign::<sr25519::Pair>("//Alice", &payload_file).expect("test case works");
+		let extrinsic = assemble(&payload_file, &signature_file).expect("test case works");
+
+		let extrinsic = extrinsic.encode();
+		let decoded =
+			decode_extrinsic(&test_metadata(), None, &extrinsic).expect("test case works").decoded;
+
+		assert_eq!(decoded.call, transfer_to_bob());
+		assert_eq!(decoded.signer, Some(AccountKeyring::Alice.to_account_id().into()));
+		assert_eq!(decoded.extra, Extra::default());
+		assert_eq!(decoded.additional_signed, None);
+
+		let decoded = Decoded { chain: Some(Chain::Polkadot), ..decoded };
+		let alice = Chain::Polkadot.format_account(&AccountKeyring::Alice.to_account_id());
+		assert!(decoded.display(None).contains(&format!("Signer: {}\n", alice)));
+	}
+
+	#[test]
+	fn verify_works() {
+		let payload_file = test_payload_file();
+		let signature_file =
+			sign::<sr25519::Pair>("//Alice", &payload_file).expect("test case works");
+		let extrinsic = assemble(&payload_file, &signature_file).expect("test case works").encode();
+		let decoded =
+			decode_extrinsic(&test_metadata(), None, &extrinsic).expect("test case works");
+
+		let checks = verify(&decoded, Chain::Kusama, 26, 4, None, None).expect("test case works");
+		assert!(checks.all_passed());
+		assert!(!display_checks(&checks).contains("FAILED"));
+
+		let checks = verify(&decoded, Chain::Kusama, 27, 4, None, None).expect("test case works");
+		assert!(!checks.signature);
+		assert!(display_checks(&checks).contains("Signature: FAILED"));
+
+		// Signed with `CheckMetadataHash` enabled, so the metadata hash is needed to verify it
+		let mut args = Address::from(AccountKeyring::Bob.to_account_id()).encode();
+		args.push(48);
+		let tx: Tx<RawArgs, KusamaRuntime> = Tx::new(TxConfig {
+			call: GenericCall::new(CallIndex::new(5, 0), RawArgs(args)),
+			address: AccountKeyring::Alice.to_account_id().into(),
+			nonce: 0,
+			tx_version: 4,
+			spec_version: 26,
+			genesis_hash: KusamaRuntime::GENESIS_HASH,
+			mortality: Mortality::Immortal,
+			tip: 0,
+			fee_asset: None,
+			metadata_hash: Some([2; 32]),
+		})
+		.expect("test case works");
+		let payload_file = PayloadFile::new(
+			&AccountKeyring::Alice.to_account_id(),
+			tx.signed_payload().expect("test case works"),
+		);
+		let signature_file =
+			sign::<sr25519::Pair>("//Alice", &payload_file).expect("test case works");
+		let extrinsic = assemble(&payload_file, &signature_file).expect("test case works").encode();
+		let decoded =
+			decode_extrinsic(&test_metadata(), None, &extrinsic).expect("test case works");
+
+		let checks =
+			verify(&decoded, Chain::Kusama, 26, 4, None, Some([2; 32])).expect("test case works");
+		assert!(checks.all_passed());
+		let checks = verify(&decoded, Chain::Kusama, 26, 4, None, None).expect("test case works");
+		assert!(!checks.signature);
+	}
+
+	#[test]
+	fn decode_and_verify_extrinsic_paying_fees_in_an_asset() {
+		let metadata = statemine_metadata();
+		let bob = Address::from(AccountKeyring::Bob.to_account_id());
+		let args = [bob.encode(), Compact(12u128).encode()].concat();
+		let construct = Construct::from_iter(&[
+			"construct",
+			"--metadata",
+			"metadata.json",
+			"--pallet",
+			"Balances",
+			"--call",
+			"transfer_keep_alive",
+			"--args",
+			&util::encode_hex(&args),
+			"--sender",
+			&util::encode_hex(AccountKeyring::Alice.to_account_id().as_ref()),
+			"--nonce",
+			"7",
+			"--spec-version",
+			"1000000",
+			"--tx-version",
+			"10",
+			"--chain",
+			"statemine",
+			"--tip",
+			"1000",
+			"--fee-asset",
+			"1984",
+		]);
+		let payload_file = construct.payload_file(&metadata).expect("test case works");
+		let signature_file =
+			sign::<sr25519::Pair>("//Alice", &payload_file).expect("test case works");
+		let extrinsic = assemble(&payload_file, &signature_file).expect("test case works").encode();
+
+		// The asset id is decoded with the type registry, never as a typed `AssetId`
+		let decoded = decode_extrinsic(&metadata, None, &extrinsic).expect("test case works");
+		assert_eq!(decoded.decoded.extra.nonce, 7);
+		assert_eq!(decoded.decoded.extra.tip, 1_000);
+		assert!(decoded.decoded.display(None).contains("Fee asset: 1984\n"));
+
+		let checks =
+			verify(&decoded, Chain::Statemine, 1_000_000, 10, None, None).expect("test case works");
+		assert!(checks.all_passed());
+		let checks =
+			verify(&decoded, Chain::Statemine, 1_000_001, 10, None, None).expect("test case works");
+		assert!(!checks.signature);
+	}
+
+	#[test]
+	fn construct_checks_the_network_prefix_of_accounts_in_values() {
+		let metadata = statemine_metadata();
+		let construct = |dest: &str| {
+			let values = format!(r#"[{{"Id": "{}"}}, 12]"#, dest);
+			Construct::from_iter(&[
+				"construct",
+				"--metadata",
+				"metadata.json",
+				"--pallet",
+				"Balances",
+				"--call",
+				"transfer_keep_alive",
+				"--values",
+				&values,
+				"--sender",
+				&util::encode_hex(AccountKeyring::Alice.to_account_id().as_ref()),
+				"--nonce",
+				"0",
+				"--spec-version",
+				"1000000",
+				"--tx-version",
+				"10",
+				"--chain",
+				"statemine",
+			])
+		};
+		let bob = AccountKeyring::Bob.to_account_id();
+
+		assert!(construct(&Chain::Statemine.format_account(&bob)).payload_file(&metadata).is_ok());
+		// Polkadot address of the destination in a Statemine transfer
+		let polkadot_bob = Chain::Polkadot.format_account(&bob);
+		assert!(construct(&polkadot_bob).payload_file(&metadata).is_err());
+	}
+
+	#[test]
+	fn decode_payload_errors_on_invalid_input() {
+		let metadata = test_metadata();
+		let payload = test_payload_file().bytes().expect("test case works");
+
+		// Truncated and trailing bytes
+		assert!(decode_payload(&metadata, None, &payload[..payload.len() - 1]).is_err());
+		assert!(decode_payload(&metadata, None, &[&payload[..], &[0]].concat()).is_err());
+		// Unknown call index
+		assert!(decode_payload(&metadata, None, &[&[5u8, 1][..], &payload[2..]].concat()).is_err());
+		// Signed extension that is not supported
+		let metadata = metadata_with_signed_extensions(&["CheckNonce", "CheckAppId"]);
+		assert!(decode_payload(&metadata, None, &payload).is_err());
+	}
+}
@@ -1,3 +1,51 @@
-fn main() {
-	println!("Stub");
+//! CLI that combines the functionality of the substrate-airgapped libraries to construct, sign and
+//! assemble transactions for `FRAME`-based chains across an online and an offline machine.
+
+mod assemble;
+mod construct;
+mod decode;
+mod payload;
+mod sign;
+mod util;
+
+use structopt::StructOpt;
+use substrate_airgapped::MultiAddress;
+
+/// Account identifier the runtimes of all chains in the `Chain` registry share, for files and
+/// inputs that are not tied to a chain. Where the chain is known, its runtime is selected with
+/// `with_runtime!`.
+type AccountId = sp_runtime::AccountId32;
+
+/// Address the runtimes of all chains in the `Chain` registry share, used to identify the signer
+/// of an extrinsic.
+type Address = MultiAddress<AccountId, u32>;
+
+/// Error type of all subcommands.
+type Error = Box<dyn std::error::Error>;
+
+/// Air-gapped construction, signing, and assembling of transactions.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "substrate-airgapped")]
+#[allow(clippy::large_enum_variant)]
+enum Command {
+	/// Construct the unsigned payload of a transaction, to be transported to the offline machine
+	/// for signing.
+	Construct(construct::Construct),
+	/// Sign a payload on the offline machine, outputting only the signature and the signer's
+	/// public key.
+	Sign(sign::Sign),
+	/// Assemble a payload and its signature into a signed extrinsic, ready to be submitted.
+	Assemble(assemble::Assemble),
+	/// Decode a payload or extrinsic into a human readable form, e.g. to inspect a payload before
+	/// signing it.
+	Decode(decode::Decode),
+}
+
+fn main() -> Result<(), Error> {
+	match Command::from_args() {
+		Command::Construct(cmd) => cmd.run(),
+		Command::Sign(cmd) => cmd.run(),
+		Command::Assemble(cmd) => cmd.run(),
+		Command::Decode(cmd) => cmd.run(),
+	}
 }
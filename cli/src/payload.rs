@@ -0,0 +1,178 @@
+use crate::{util, AccountId, Error};
+use codec::{DecodeAll, Encode};
+use core::convert::TryFrom;
+use serde::{Deserialize, Serialize};
+use sp_core::{ecdsa, ed25519, sr25519};
+use sp_runtime::{generic, traits::SignedExtension, MultiSignature, MultiSigner};
+use substrate_airgapped::{
+	DynamicExtra, DynamicSignedPayload, GenericCall, RawAdditionalSigned, RawArgs,
+};
+
+/// Transport format of a transaction's `SignedPayload` between the online and offline machine.
+///
+/// The components of the payload are kept apart so the payload can be turned into an extrinsic
+/// without knowing the types of the call arguments.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PayloadFile {
+	/// Hex of the account expected to sign the payload.
+	pub sender: String,
+	/// Hex of the SCALE encoded call, including the call index.
+	pub call: String,
+	/// Hex of the SCALE encoded transaction extra.
+	pub extra: String,
+	/// Hex of the SCALE encoded additional signed data of the transaction extra.
+	pub additional_signed: String,
+}
+
+impl PayloadFile {
+	/// Create a `PayloadFile` from a `SignedPayload`, of any of the runtimes of the `Chain`
+	/// registry, and the account expected to sign it.
+	pub fn new<E: SignedExtension>(
+		sender: &AccountId,
+		payload: generic::SignedPayload<GenericCall<RawArgs>, E>,
+	) -> Self {
+		let (call, extra, additional_signed) = payload.deconstruct();
+
+		PayloadFile {
+			sender: util::encode_hex(sender.as_ref()),
+			call: util::encode_hex(&call.encode()),
+			extra: util::encode_hex(&extra.encode()),
+			additional_signed: util::encode_hex(&additional_signed.encode()),
+		}
+	}
+
+	/// Account expected to sign the payload.
+	pub fn sender(&self) -> Result<AccountId, Error> {
+		Ok(AccountId::decode_all(&util::decode_hex(&self.sender)?)?)
+	}
+
+	/// Decode the components back into a `DynamicSignedPayload`.
+	///
+	/// The extra and additional signed data are kept as the bytes written by `construct`, so the
+	/// payload encodes to exactly `bytes`, whatever signed extensions the runtime has.
+	pub fn signed_payload(&self) -> Result<DynamicSignedPayload<RawArgs>, Error> {
+		let call = GenericCall::decode_all(&util::decode_hex(&self.call)?)?;
+		let additional_signed = util::decode_hex(&self.additional_signed)?;
+		let extra =
+			DynamicExtra::from_raw(util::decode_hex(&self.extra)?, additional_signed.clone());
+
+		Ok(DynamicSignedPayload::from_raw(call, extra, RawAdditionalSigned(additional_signed)))
+	}
+
+	/// The bytes of the SCALE encoded `SignedPayload`.
+	///
+	/// Unlike `SignedPayload::encode`, this never hashes the payload; `SigningBytes::new` does.
+	pub fn bytes(&self) -> Result<Vec<u8>, Error> {
+		let mut bytes = util::decode_hex(&self.call)?;
+		bytes.extend(util::decode_hex(&self.extra)?);
+		bytes.extend(util::decode_hex(&self.additional_signed)?);
+
+		Ok(bytes)
+	}
+}
+
+/// Transport format of the signature created on the offline machine.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SignatureFile {
+	/// Hex of the public key of the signer.
+	pub signer: String,
+	/// Hex of the SCALE encoded `MultiSignature`.
+	pub signature: String,
+}
+
+impl SignatureFile {
+	/// Create a `SignatureFile` from the signer's public key and the signature.
+	pub fn new(signer: &[u8], signature: &MultiSignature) -> Self {
+		SignatureFile {
+			signer: util::encode_hex(signer),
+			signature: util::encode_hex(&signature.encode()),
+		}
+	}
+
+	/// The signature.
+	pub fn signature(&self) -> Result<MultiSignature, Error> {
+		Ok(MultiSignature::decode_all(&util::decode_hex(&self.signature)?)?)
+	}
+
+	/// The signer, with the cryptographic scheme of its public key taken from the signature.
+	pub fn signer(&self) -> Result<MultiSigner, Error> {
+		let public = util::decode_hex(&self.signer)?;
+		let invalid_public = |_| "Public key does not match the scheme of the signature";
+		let signer = match self.signature()? {
+			MultiSignature::Sr25519(_) => MultiSigner::Sr25519(
+				sr25519::Public::try_from(&public[..]).map_err(invalid_public)?,
+			),
+			MultiSignature::Ed25519(_) => MultiSigner::Ed25519(
+				ed25519::Public::try_from(&public[..]).map_err(invalid_public)?,
+			),
+			MultiSignature::Ecdsa(_) => {
+				MultiSigner::Ecdsa(ecdsa::Public::try_from(&public[..]).map_err(invalid_public)?)
+			}
+		};
+
+		Ok(signer)
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use crate::Address;
+	use sp_keyring::AccountKeyring;
+	use substrate_airgapped::{CallIndex, KusamaRuntime, Mortality, Tx, TxConfig};
+
+	/// Construct the signed payload of a transfer from Alice to Bob for use in tests.
+	pub(crate) fn test_payload_file() -> PayloadFile {
+		// `Transfer { to: Bob, amount: 12 }`
+		let mut args = Address::from(AccountKeyring::Bob.to_account_id()).encode();
+		args.push(48);
+		let call = GenericCall::new(CallIndex::new(5, 0), RawArgs(args));
+		let tx: Tx<RawArgs, KusamaRuntime> = Tx::new(TxConfig {
+			call,
+			address: AccountKeyring::Alice.to_account_id().into(),
+			nonce: 0,
+			tx_version: 4,
+			spec_version: 26,
+			genesis_hash: KusamaRuntime::GENESIS_HASH,
+			mortality: Mortality::Immortal,
+			tip: 0,
+			fee_asset: None,
+			metadata_hash: None,
+		})
+		.expect("test case works");
+
+		PayloadFile::new(
+			&AccountKeyring::Alice.to_account_id(),
+			tx.signed_payload().expect("test case works"),
+		)
+	}
+
+	#[test]
+	fn payload_file_decodes_to_signed_payload() {
+		let file = test_payload_file();
+		let payload = file.signed_payload().expect("test case works");
+
+		assert_eq!(file.sender().expect("test case works"), AccountKeyring::Alice.to_account_id());
+		assert_eq!(PayloadFile::new(&AccountKeyring::Alice.to_account_id(), payload), file);
+
+		let mut trailing_bytes = file.clone();
+		trailing_bytes.sender.push_str("00");
+		assert!(trailing_bytes.sender().is_err());
+	}
+
+	#[test]
+	fn payload_file_bytes_match_signed_payload() {
+		let file = test_payload_file();
+		let expected = file.signed_payload().expect("test case works").encode();
+
+		assert_eq!(file.extra, "0x00000000");
+		assert_eq!(file.bytes().expect("test case works"), expected);
+
+		// Extra of signed extensions the `Extra` of the runtime does not have is kept
+		let mut file = file;
+		file.extra = "0x0000000001".into();
+		file.additional_signed.push_str("01");
+		let payload = file.signed_payload().expect("test case works");
+		assert_eq!(file.bytes().expect("test case works"), payload.encode());
+	}
+}
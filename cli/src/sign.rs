@@ -0,0 +1,164 @@
+use crate::{
+	payload::{PayloadFile, SignatureFile},
+	util, Error,
+};
+use sp_core::{ecdsa, ed25519, sr25519, Pair};
+use sp_runtime::{traits::IdentifyAccount, MultiSignature, MultiSigner};
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+use structopt::StructOpt;
+use substrate_airgapped::SigningBytes;
+
+/// Cryptographic scheme of a key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scheme {
+	/// Schnorr signatures over Ristretto.
+	Sr25519,
+	/// Edwards curve signatures.
+	Ed25519,
+	/// ECDSA signatures over secp256k1.
+	Ecdsa,
+}
+
+impl Scheme {
+	/// Names of the variants, as accepted by `from_str`.
+	pub const VARIANTS: &'static [&'static str] = &["sr25519", "ed25519", "ecdsa"];
+}
+
+impl FromStr for Scheme {
+	type Err = &'static str;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"sr25519" => Ok(Scheme::Sr25519),
+			"ed25519" => Ok(Scheme::Ed25519),
+			"ecdsa" => Ok(Scheme::Ecdsa),
+			_ => Err("Unknown cryptographic scheme"),
+		}
+	}
+}
+
+/// Options for the `sign` subcommand.
+#[derive(Debug, StructOpt)]
+pub struct Sign {
+	/// Path to the payload file created by `construct`.
+	#[structopt(long, parse(from_os_str))]
+	payload: PathBuf,
+	/// Path to a file containing the secret key URI of the signer: a mnemonic phrase or hex seed,
+	/// with optional derivation path and password (e.g. `"<mnemonic>//hard/soft///password"`).
+	/// Read from stdin if not given, so the secret is never part of the command line.
+	#[structopt(long, parse(from_os_str))]
+	keyfile: Option<PathBuf>,
+	/// Cryptographic scheme of the key.
+	#[structopt(long, default_value = "sr25519", possible_values = Scheme::VARIANTS)]
+	scheme: Scheme,
+	/// File to write the signature file to. Defaults to stdout.
+	#[structopt(long, short, parse(from_os_str))]
+	output: Option<PathBuf>,
+}
+
+impl Sign {
+	/// Sign the payload and write out the signature file.
+	pub fn run(self) -> Result<(), Error> {
+		let suri = read_suri(self.keyfile.as_deref())?;
+		let payload_file: PayloadFile = util::read_json(&self.payload)?;
+
+		let signature_file = match self.scheme {
+			Scheme::Sr25519 => sign::<sr25519::Pair>(&suri, &payload_file)?,
+			Scheme::Ed25519 => sign::<ed25519::Pair>(&suri, &payload_file)?,
+			Scheme::Ecdsa => sign::<ecdsa::Pair>(&suri, &payload_file)?,
+		};
+
+		util::write_output(self.output.as_deref(), &serde_json::to_string_pretty(&signature_file)?)
+	}
+}
+
+/// Read the secret key URI from the file at `keyfile`, or the first line of stdin if no path is
+/// given.
+fn read_suri(keyfile: Option<&Path>) -> Result<String, Error> {
+	let suri = match keyfile {
+		Some(keyfile) => fs::read_to_string(keyfile)?,
+		None => {
+			eprintln!("Secret key URI:");
+			let mut suri = String::new();
+			io::stdin().read_line(&mut suri)?;
+			suri
+		}
+	};
+	let suri = suri.trim();
+	if suri.is_empty() {
+		return Err("A secret key URI is required".into());
+	}
+
+	Ok(suri.to_owned())
+}
+
+/// Sign the payload of `payload_file` with the key derived from `suri`, the same way
+/// `Tx::signed_tx_from_pair` does: the bytes of the payload are signed as they are, or their hash
+/// if they are longer than 256 bytes.
+///
+/// Errors if the key does not belong to the sender of the payload.
+pub fn sign<P>(suri: &str, payload_file: &PayloadFile) -> Result<SignatureFile, Error>
+where
+	P: Pair,
+	MultiSignature: From<P::Signature>,
+	MultiSigner: From<P::Public>,
+{
+	let pair = P::from_string(suri, None).map_err(|_| "Invalid secret key URI")?;
+	let public = pair.public();
+	if MultiSigner::from(public.clone()).into_account() != payload_file.sender()? {
+		return Err("Key does not belong to the sender of the payload".into());
+	}
+
+	let signing_bytes = SigningBytes::new(payload_file.bytes()?);
+	let signature: MultiSignature = pair.sign(&signing_bytes.bytes).into();
+
+	Ok(SignatureFile::new(public.as_ref(), &signature))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::payload::tests::test_payload_file;
+	use sp_keyring::AccountKeyring;
+	use sp_runtime::traits::Verify;
+
+	#[test]
+	fn sign_creates_valid_signature() {
+		let payload_file = test_payload_file();
+		let signature_file =
+			sign::<sr25519::Pair>("//Alice", &payload_file).expect("test case works");
+
+		assert_eq!(
+			signature_file.signer,
+			util::encode_hex(AccountKeyring::Alice.public().as_ref())
+		);
+		let signature = signature_file.signature().expect("test case works");
+		let payload = payload_file.bytes().expect("test case works");
+		assert!(signature.verify(&payload[..], &AccountKeyring::Alice.to_account_id()));
+	}
+
+	#[test]
+	fn sign_hashes_long_payloads() {
+		let mut payload_file = test_payload_file();
+		payload_file.call.push_str(&"00".repeat(256));
+		let signature_file =
+			sign::<sr25519::Pair>("//Alice", &payload_file).expect("test case works");
+
+		let signature = signature_file.signature().expect("test case works");
+		let payload = payload_file.bytes().expect("test case works");
+		let hash = sp_core::blake2_256(&payload);
+		assert!(signature.verify(&hash[..], &AccountKeyring::Alice.to_account_id()));
+	}
+
+	#[test]
+	fn sign_rejects_key_of_other_account() {
+		let payload_file = test_payload_file();
+
+		assert!(sign::<sr25519::Pair>("//Bob", &payload_file).is_err());
+		assert!(sign::<ed25519::Pair>("//Alice", &payload_file).is_err());
+	}
+}
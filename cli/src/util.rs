@@ -0,0 +1,201 @@
+use crate::{AccountId, Error};
+use core::convert::TryFrom;
+use metadata::{Fields, Metadata, Value};
+use serde::{de::DeserializeOwned, Deserialize};
+use sp_core::H256;
+use std::{fs, path::Path};
+use substrate_airgapped::{AssetId, Chain};
+
+/// RPC response JSON object
+#[derive(Deserialize)]
+struct RpcRes<T> {
+	result: T,
+}
+
+/// Decode a hex string, with or without a leading `0x`.
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>, Error> {
+	Ok(hex::decode(hex.trim().trim_start_matches("0x"))?)
+}
+
+/// Hex encode bytes, with a leading `0x`.
+pub fn encode_hex(bytes: &[u8]) -> String {
+	format!("0x{}", hex::encode(bytes))
+}
+
+/// Parse a 32 byte hash from a hex string.
+pub fn parse_h256(hex: &str) -> Result<H256, Error> {
+	let bytes = decode_hex(hex)?;
+	if bytes.len() != H256::len_bytes() {
+		return Err("Expected a 32 byte hash".into());
+	}
+
+	Ok(H256::from_slice(&bytes))
+}
+
+/// Parse an account from hex, or from an SS58 address with the network prefix of `chain`.
+pub fn parse_account(account: &str, chain: Chain) -> Result<AccountId, Error> {
+	if account.starts_with("0x") {
+		let bytes = decode_hex(account)?;
+		return Ok(AccountId::try_from(&bytes[..]).map_err(|_| "Expected a 32 byte account")?);
+	}
+
+	Ok(chain.parse_account(account)?)
+}
+
+/// Parse the id of an asset to pay fees in, either a `u32` asset id or the hex of the SCALE encoded
+/// asset id of the runtime (e.g. of an XCM `Location`).
+pub fn parse_asset_id(asset_id: &str) -> Result<AssetId, Error> {
+	if asset_id.starts_with("0x") {
+		return Ok(AssetId(decode_hex(asset_id)?));
+	}
+
+	Ok(asset_id.parse::<u32>()?.into())
+}
+
+/// The chain of the `Chain` registry with the given genesis hash.
+pub fn chain_from_genesis_hash(genesis_hash: &H256) -> Result<Chain, Error> {
+	Chain::from_genesis_hash(genesis_hash.as_bytes()).ok_or_else(|| {
+		format!("Unknown genesis hash {}", encode_hex(genesis_hash.as_bytes())).into()
+	})
+}
+
+/// Format an amount of the smallest unit of a chain's native token in whole tokens, e.g.
+/// `1.5 KSM` for `1_500_000_000_000`.
+pub fn format_balance(amount: u128, chain: Chain) -> String {
+	let unit = 10u128.pow(chain.token_decimals().into());
+	let fraction = format!("{:0width$}", amount % unit, width = chain.token_decimals().into());
+	let fraction = fraction.trim_end_matches('0');
+	if fraction.is_empty() {
+		format!("{} {}", amount / unit, chain.token_symbol())
+	} else {
+		format!("{}.{} {}", amount / unit, fraction, chain.token_symbol())
+	}
+}
+
+/// Read runtime metadata from a file.
+///
+/// The file is expected to contain either the hex of the SCALE encoded metadata or the JSON
+/// response to a `state_getMetadata` RPC:
+///
+/// ```text
+/// {"jsonrpc":"2.0","result":"0x6d657461...","id":1}
+/// ```
+pub fn read_metadata(path: &Path) -> Result<Metadata, Error> {
+	let contents = fs::read_to_string(path)?;
+	let contents = contents.trim();
+	let bytes = if contents.starts_with('{') {
+		let rpc_res: RpcRes<String> = serde_json::from_str(contents)?;
+		decode_hex(&rpc_res.result)?
+	} else {
+		decode_hex(contents)?
+	};
+
+	Ok(Metadata::from_bytes(&bytes)?)
+}
+
+/// Convert JSON into a `Value` of a call argument.
+///
+/// Objects become composites with named fields, which also express enum variants as
+/// `{"Variant": fields}`. Numbers too large for JSON can be given as strings.
+pub fn json_to_value(json: serde_json::Value) -> Result<Value, Error> {
+	let value = match json {
+		serde_json::Value::Null => return Err("JSON null is not a valid argument value".into()),
+		serde_json::Value::Bool(b) => Value::Bool(b),
+		serde_json::Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+			(Some(n), _) => Value::UInt(n.into()),
+			(None, Some(n)) => Value::Int(n.into()),
+			_ => return Err("Expected an integer".into()),
+		},
+		serde_json::Value::String(s) => Value::Str(s),
+		serde_json::Value::Array(values) => {
+			Value::Sequence(values.into_iter().map(json_to_value).collect::<Result<_, _>>()?)
+		}
+		serde_json::Value::Object(fields) => Value::Composite(Fields::Named(
+			fields
+				.into_iter()
+				.map(|(name, value)| Ok((name, json_to_value(value)?)))
+				.collect::<Result<_, Error>>()?,
+		)),
+	};
+
+	Ok(value)
+}
+
+/// Read a JSON file into `T`.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, Error> {
+	Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Write `contents` to the file at `path`, or to stdout if no path is given.
+pub fn write_output(path: Option<&Path>, contents: &str) -> Result<(), Error> {
+	match path {
+		Some(path) => fs::write(path, contents)?,
+		None => println!("{}", contents),
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_account_works() {
+		let alice = sp_keyring::AccountKeyring::Alice.to_account_id();
+		let hex = encode_hex(alice.as_ref());
+
+		assert_eq!(parse_account(&hex, Chain::Polkadot).expect("test case works"), alice);
+		assert_eq!(
+			parse_account(&Chain::Kusama.format_account(&alice), Chain::Kusama)
+				.expect("test case works"),
+			alice
+		);
+		assert!(parse_account(&Chain::Kusama.format_account(&alice), Chain::Polkadot).is_err());
+		assert!(parse_account("0x0102", Chain::Polkadot).is_err());
+	}
+
+	#[test]
+	fn parse_asset_id_works() {
+		assert_eq!(parse_asset_id("1984").expect("test case works"), AssetId(vec![192, 7, 0, 0]));
+		assert_eq!(parse_asset_id("0x0100").expect("test case works"), AssetId(vec![1, 0]));
+		assert!(parse_asset_id("USDT").is_err());
+	}
+
+	#[test]
+	fn format_balance_works() {
+		assert_eq!(format_balance(0, Chain::Kusama), "0 KSM");
+		assert_eq!(format_balance(1_500_000_000_000, Chain::Kusama), "1.5 KSM");
+		assert_eq!(format_balance(12, Chain::Polkadot), "0.0000000012 DOT");
+		assert_eq!(format_balance(30_000_000_000, Chain::Polkadot), "3 DOT");
+	}
+
+	#[test]
+	fn json_to_value_works() {
+		let json = serde_json::json!([{ "Id": "5FHneW46..." }, 12, -1, "340282366920938463463374607431768211455", [true]]);
+		let values: Vec<Value> = match json {
+			serde_json::Value::Array(values) => values
+				.into_iter()
+				.map(json_to_value)
+				.collect::<Result<_, _>>()
+				.expect("test case works"),
+			_ => unreachable!(),
+		};
+
+		assert_eq!(
+			values,
+			vec![
+				Value::Composite(Fields::Named(vec![(
+					"Id".into(),
+					Value::Str("5FHneW46...".into())
+				)])),
+				Value::UInt(12),
+				Value::Int(-1),
+				Value::Str("340282366920938463463374607431768211455".into()),
+				Value::Sequence(vec![Value::Bool(true)]),
+			]
+		);
+		assert!(json_to_value(serde_json::json!(null)).is_err());
+		assert!(json_to_value(serde_json::json!(1.5)).is_err());
+	}
+}
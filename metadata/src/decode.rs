@@ -0,0 +1,757 @@
+use crate::{
+	value::{Fields, Value},
+	Metadata,
+};
+use core::convert::TryFrom;
+use scale::{Compact, Decode};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use sp_core::crypto::AccountId32;
+use substrate_airgapped::{CallIndex, Error};
+
+/// Call decoded with the type registry of V14 metadata.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedCall {
+	/// Name of the pallet.
+	pub pallet: String,
+	/// Name of the call.
+	pub call: String,
+	/// Names and values of the arguments, in the order they are encoded.
+	pub arguments: Vec<(String, Value)>,
+}
+
+impl Metadata {
+	/// Decode a call, including its call index, consuming only its bytes from `input`.
+	///
+	/// With V14 metadata onwards, the arguments are decoded by walking the type registry. Before,
+	/// they are decoded by the name of their type, so only commonly used types are supported.
+	/// Values of `AccountId32` are decoded as `Value::Account`.
+	pub fn decode_call(&self, input: &mut &[u8]) -> Result<DecodedCall, Error> {
+		self.decode_nested_call(0, input)
+	}
+
+	/// Decode a call nested in `depth` values, e.g. of `Utility.batch`, see `decode_call`.
+	fn decode_nested_call(&self, depth: u32, input: &mut &[u8]) -> Result<DecodedCall, Error> {
+		let call_index = CallIndex::new(decode(input)?, decode(input)?);
+		let (pallet, call_metadata) = self.call(&call_index)?;
+
+		let arguments = call_metadata
+			.arguments()
+			.iter()
+			.map(|arg| {
+				let value = match self.types() {
+					Some(types) => {
+						let type_id = arg.type_id().ok_or("Call argument has no type id")?;
+						decode_value(types, type_id, depth, input)?
+					}
+					None => decode_value_by_type_name(self, arg.ty(), depth, input)?,
+				};
+				Ok((arg.name().to_owned(), value))
+			})
+			.collect::<Result<_, Error>>()?;
+
+		Ok(DecodedCall {
+			pallet: pallet.to_owned(),
+			call: call_metadata.name().to_owned(),
+			arguments,
+		})
+	}
+
+	/// Decode the extra data of the signed extension `identifier` of a transaction, consuming
+	/// only its bytes from `input`, e.g. for extensions whose data has a runtime specific type
+	/// like the asset id of `ChargeAssetTxPayment`.
+	///
+	/// Requires V14 metadata onwards, as the data is decoded by walking the type registry.
+	pub fn decode_signed_extension(
+		&self,
+		identifier: &str,
+		input: &mut &[u8],
+	) -> Result<Value, Error> {
+		let types =
+			self.types().ok_or("Decoding signed extensions requires V14 metadata or later")?;
+		let type_id = self
+			.signed_extension_types
+			.get(identifier)
+			.ok_or("Signed extension could not be found in runtime metadata")?;
+
+		decode_value(types, *type_id, 0, input)
+	}
+}
+
+/// Maximum nesting depth of decoded values, as the `DecodeLimit` runtimes decode calls with, so
+/// recursive types like nested calls can not exhaust the stack.
+const MAX_DEPTH: u32 = 256;
+
+/// Decode a value of the type with `type_id` in `types`, consuming only its bytes from `input`.
+/// `depth` is the number of values the value is nested in.
+pub(crate) fn decode_value(
+	types: &PortableRegistry,
+	type_id: u32,
+	depth: u32,
+	input: &mut &[u8],
+) -> Result<Value, Error> {
+	if depth >= MAX_DEPTH {
+		return Err("Value is nested too deeply".into());
+	}
+	let depth = depth + 1;
+	let ty = types.resolve(type_id).ok_or("Type could not be found in the type registry")?;
+	let value = match &ty.type_def {
+		TypeDef::Composite(_)
+			if ty.path.segments.last().map(String::as_str) == Some("AccountId32") =>
+		{
+			Value::Account(AccountId32::from(decode::<[u8; 32]>(input)?))
+		}
+		TypeDef::Composite(composite) => {
+			Value::Composite(decode_fields(types, &composite.fields, depth, input)?)
+		}
+		TypeDef::Variant(variant) => {
+			let index: u8 = decode(input)?;
+			let variant = variant
+				.variants
+				.iter()
+				.find(|variant| variant.index == index)
+				.ok_or("Enum variant could not be found in the type registry")?;
+			Value::Variant(
+				variant.name.clone(),
+				decode_fields(types, &variant.fields, depth, input)?,
+			)
+		}
+		TypeDef::Sequence(sequence) => {
+			let len = decode::<Compact<u32>>(input)?.0;
+			decode_values(types, sequence.type_param.id, usize::try_from(len)?, depth, input)?
+		}
+		TypeDef::Array(array) => {
+			decode_values(types, array.type_param.id, usize::try_from(array.len)?, depth, input)?
+		}
+		TypeDef::Tuple(tuple) => Value::Sequence(
+			tuple
+				.fields
+				.iter()
+				.map(|ty| decode_value(types, ty.id, depth, input))
+				.collect::<Result<_, Error>>()?,
+		),
+		TypeDef::Primitive(primitive) => decode_primitive(primitive, input)?,
+		TypeDef::Compact(compact) => {
+			Value::Compact(Box::new(decode_compact(types, compact.type_param.id, input)?))
+		}
+		TypeDef::BitSequence(_) => return Err("Bit sequences are not supported".into()),
+	};
+
+	Ok(value)
+}
+
+fn decode_fields(
+	types: &PortableRegistry,
+	fields: &[Field<PortableForm>],
+	depth: u32,
+	input: &mut &[u8],
+) -> Result<Fields, Error> {
+	// Fields are either all named or all unnamed
+	if fields.iter().all(|field| field.name.is_some()) && !fields.is_empty() {
+		let named = fields
+			.iter()
+			.map(|field| {
+				let name = field.name.clone().unwrap_or_default();
+				Ok((name, decode_value(types, field.ty.id, depth, input)?))
+			})
+			.collect::<Result<_, Error>>()?;
+		return Ok(Fields::Named(named));
+	}
+
+	let unnamed = fields
+		.iter()
+		.map(|field| decode_value(types, field.ty.id, depth, input))
+		.collect::<Result<_, Error>>()?;
+
+	Ok(Fields::Unnamed(unnamed))
+}
+
+/// Decode `len` values of the type with `type_id`, as `Value::Bytes` if the type is `u8`.
+fn decode_values(
+	types: &PortableRegistry,
+	type_id: u32,
+	len: usize,
+	depth: u32,
+	input: &mut &[u8],
+) -> Result<Value, Error> {
+	let ty = types.resolve(type_id).ok_or("Type could not be found in the type registry")?;
+	if ty.type_def == TypeDef::Primitive(TypeDefPrimitive::U8) {
+		if input.len() < len {
+			return Err("Not enough data to fill buffer".into());
+		}
+		let (bytes, rest) = input.split_at(len);
+		*input = rest;
+		return Ok(Value::Bytes(bytes.to_vec()));
+	}
+
+	let values =
+		(0..len).map(|_| decode_value(types, type_id, depth, input)).collect::<Result<_, _>>()?;
+
+	Ok(Value::Sequence(values))
+}
+
+fn decode_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<Value, Error> {
+	let value = match primitive {
+		TypeDefPrimitive::Bool => Value::Bool(decode(input)?),
+		TypeDefPrimitive::Char => {
+			Value::Char(char::from_u32(decode(input)?).ok_or("Invalid char")?)
+		}
+		TypeDefPrimitive::Str => Value::Str(decode(input)?),
+		TypeDefPrimitive::U8 => Value::UInt(decode::<u8>(input)?.into()),
+		TypeDefPrimitive::U16 => Value::UInt(decode::<u16>(input)?.into()),
+		TypeDefPrimitive::U32 => Value::UInt(decode::<u32>(input)?.into()),
+		TypeDefPrimitive::U64 => Value::UInt(decode::<u64>(input)?.into()),
+		TypeDefPrimitive::U128 => Value::UInt(decode(input)?),
+		TypeDefPrimitive::I8 => Value::Int(decode::<i8>(input)?.into()),
+		TypeDefPrimitive::I16 => Value::Int(decode::<i16>(input)?.into()),
+		TypeDefPrimitive::I32 => Value::Int(decode::<i32>(input)?.into()),
+		TypeDefPrimitive::I64 => Value::Int(decode::<i64>(input)?.into()),
+		TypeDefPrimitive::I128 => Value::Int(decode(input)?),
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => {
+			return Err("256 bit integers are not supported".into())
+		}
+	};
+
+	Ok(value)
+}
+
+fn decode_compact(
+	types: &PortableRegistry,
+	type_id: u32,
+	input: &mut &[u8],
+) -> Result<Value, Error> {
+	let ty = types.resolve(type_id).ok_or("Type could not be found in the type registry")?;
+	let value = match &ty.type_def {
+		TypeDef::Primitive(TypeDefPrimitive::U8) => {
+			Value::UInt(decode::<Compact<u8>>(input)?.0.into())
+		}
+		TypeDef::Primitive(TypeDefPrimitive::U16) => {
+			Value::UInt(decode::<Compact<u16>>(input)?.0.into())
+		}
+		TypeDef::Primitive(TypeDefPrimitive::U32) => {
+			Value::UInt(decode::<Compact<u32>>(input)?.0.into())
+		}
+		TypeDef::Primitive(TypeDefPrimitive::U64) => {
+			Value::UInt(decode::<Compact<u64>>(input)?.0.into())
+		}
+		TypeDef::Primitive(TypeDefPrimitive::U128) => {
+			Value::UInt(decode::<Compact<u128>>(input)?.0)
+		}
+		// Compact encoded wrapper types, e.g. `Compact<Perbill>`
+		TypeDef::Composite(composite) if composite.fields.len() == 1 => {
+			let inner = decode_compact(types, composite.fields[0].ty.id, input)?;
+			let fields = match &composite.fields[0].name {
+				Some(name) => Fields::Named(vec![(name.clone(), inner)]),
+				None => Fields::Unnamed(vec![inner]),
+			};
+			Value::Composite(fields)
+		}
+		_ => return Err("Unsupported compact type".into()),
+	};
+
+	Ok(value)
+}
+
+/// Decode a value by the name of its type, for metadata before V14 which has no type registry.
+/// Values are decoded to what the type registry would decode them to, e.g. a nested call to the
+/// variant of its pallet in the outer `Call` enum. `depth` is the number of values the value is
+/// nested in.
+fn decode_value_by_type_name(
+	metadata: &Metadata,
+	ty: &str,
+	depth: u32,
+	input: &mut &[u8],
+) -> Result<Value, Error> {
+	if depth >= MAX_DEPTH {
+		return Err("Value is nested too deeply".into());
+	}
+	let depth = depth + 1;
+	let ty: String = ty.replace("T::", "").chars().filter(|c| !c.is_whitespace()).collect();
+
+	if let Some(inner) = type_parameter(&ty, "Box") {
+		return decode_value_by_type_name(metadata, inner, depth, input);
+	}
+	if type_parameter(&ty, "Compact").is_some() {
+		// The compact encoding of an integer is the same for all widths.
+		return Ok(Value::Compact(Box::new(Value::UInt(decode::<Compact<u128>>(input)?.0))));
+	}
+	if let Some(inner) = type_parameter(&ty, "Option") {
+		let value = match decode::<u8>(input)? {
+			0 => Value::Variant("None".into(), Fields::Unnamed(vec![])),
+			1 => Value::Variant(
+				"Some".into(),
+				Fields::Unnamed(vec![decode_value_by_type_name(metadata, inner, depth, input)?]),
+			),
+			_ => return Err("Invalid `Option` variant".into()),
+		};
+		return Ok(value);
+	}
+	if ty == "Vec<u8>" || ty == "Bytes" {
+		return Ok(Value::Bytes(decode(input)?));
+	}
+	if let Some(inner) = type_parameter(&ty, "Vec") {
+		let len = decode::<Compact<u32>>(input)?.0;
+		let values = (0..len)
+			.map(|_| decode_value_by_type_name(metadata, inner, depth, input))
+			.collect::<Result<_, Error>>()?;
+		return Ok(Value::Sequence(values));
+	}
+	if ty == "Call" || ty.ends_with(">::Call") {
+		let DecodedCall { pallet, call, arguments } = metadata.decode_nested_call(depth, input)?;
+		let call = Value::Variant(call, Fields::Named(arguments));
+		return Ok(Value::Variant(pallet, Fields::Unnamed(vec![call])));
+	}
+	if ty == "AccountId" {
+		return Ok(Value::Account(AccountId32::from(decode::<[u8; 32]>(input)?)));
+	}
+	if ty == "Address" || ty == "LookupSource" || ty.ends_with("StaticLookup>::Source") {
+		return decode_address(input);
+	}
+
+	let value = match ty.as_str() {
+		"bool" => Value::Bool(decode(input)?),
+		"u8" => Value::UInt(decode::<u8>(input)?.into()),
+		"u16" => Value::UInt(decode::<u16>(input)?.into()),
+		"u32" | "BlockNumber" | "Index" => Value::UInt(decode::<u32>(input)?.into()),
+		"u64" | "Moment" => Value::UInt(decode::<u64>(input)?.into()),
+		"u128" | "Balance" | "BalanceOf<T>" => Value::UInt(decode(input)?),
+		"Hash" | "H256" => Value::Bytes(decode::<[u8; 32]>(input)?.to_vec()),
+		_ => return Err("Unsupported argument type".into()),
+	};
+
+	Ok(value)
+}
+
+/// Get the type parameter of `ty` if it is the generic type `outer` (e.g. `u32` for `Vec<u32>`).
+fn type_parameter<'a>(ty: &'a str, outer: &str) -> Option<&'a str> {
+	ty.strip_prefix(outer)?.strip_prefix('<')?.strip_suffix('>')
+}
+
+/// Decode a `MultiAddress` with 32 byte accounts and `u32` indices, the address of the runtimes
+/// of the `Chain` registry.
+fn decode_address(input: &mut &[u8]) -> Result<Value, Error> {
+	let (variant, value) = match decode::<u8>(input)? {
+		0 => ("Id", Value::Account(AccountId32::from(decode::<[u8; 32]>(input)?))),
+		1 => (
+			"Index",
+			Value::Compact(Box::new(Value::UInt(decode::<Compact<u32>>(input)?.0.into()))),
+		),
+		2 => ("Raw", Value::Bytes(decode(input)?)),
+		3 => ("Address32", Value::Bytes(decode::<[u8; 32]>(input)?.to_vec())),
+		4 => ("Address20", Value::Bytes(decode::<[u8; 20]>(input)?.to_vec())),
+		_ => return Err("Invalid `MultiAddress` variant".into()),
+	};
+
+	Ok(Value::Variant(variant.into(), Fields::Unnamed(vec![value])))
+}
+
+fn decode<T: Decode>(input: &mut &[u8]) -> Result<T, Error> {
+	T::decode(input).map_err(|_| "Failed to decode call".into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::RuntimeMetadataPrefixed;
+	use core::convert::TryInto;
+	use frame_metadata::{
+		decode_different::DecodeDifferent,
+		v12,
+		v14::{
+			ExtrinsicMetadata, PalletCallMetadata, PalletMetadata, RuntimeMetadataV14,
+			SignedExtensionMetadata,
+		},
+		RuntimeMetadata, META_RESERVED,
+	};
+	use scale::Encode;
+	use scale_info::{meta_type, TypeInfo};
+	use sp_core::crypto::{Ss58AddressFormat, Ss58Codec};
+
+	#[derive(TypeInfo, Encode)]
+	#[codec(crate = scale)]
+	struct AccountId32([u8; 32]);
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo, Encode)]
+	#[codec(crate = scale)]
+	enum MultiAddress {
+		Id(AccountId32),
+		Index(#[codec(compact)] u32),
+	}
+
+	#[allow(dead_code, non_camel_case_types)]
+	#[derive(TypeInfo, Encode)]
+	#[codec(crate = scale)]
+	enum BalancesCall {
+		#[codec(index = 3)]
+		transfer_keep_alive {
+			dest: MultiAddress,
+			#[codec(compact)]
+			value: u128,
+		},
+	}
+
+	#[allow(dead_code, non_camel_case_types)]
+	#[derive(TypeInfo, Encode)]
+	#[codec(crate = scale)]
+	enum UtilityCall {
+		batch { calls: Vec<RuntimeCall> },
+		as_derivative { index: u16, call: Box<RuntimeCall> },
+		remark { remark: Vec<u8>, labels: (bool, Option<i8>) },
+	}
+
+	#[derive(TypeInfo, Encode)]
+	#[codec(crate = scale)]
+	struct ChargeAssetTxPayment {
+		#[codec(compact)]
+		tip: u128,
+		asset_id: Option<u32>,
+	}
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo, Encode)]
+	#[codec(crate = scale)]
+	enum RuntimeCall {
+		#[codec(index = 4)]
+		Balances(BalancesCall),
+		#[codec(index = 26)]
+		Utility(UtilityCall),
+	}
+
+	fn metadata() -> Metadata {
+		let pallet = |name, index, ty| PalletMetadata {
+			name,
+			storage: None,
+			calls: Some(PalletCallMetadata { ty }),
+			event: None,
+			constants: vec![],
+			error: None,
+			index,
+		};
+		let pallets = vec![
+			pallet("Balances", 4, meta_type::<BalancesCall>()),
+			pallet("Utility", 26, meta_type::<UtilityCall>()),
+		];
+		let signed_extensions = vec![SignedExtensionMetadata {
+			identifier: "ChargeAssetTxPayment",
+			ty: meta_type::<ChargeAssetTxPayment>(),
+			additional_signed: meta_type::<()>(),
+		}];
+		let extrinsic = ExtrinsicMetadata { ty: meta_type::<()>(), version: 4, signed_extensions };
+		let metadata: RuntimeMetadataPrefixed =
+			RuntimeMetadataV14::new(pallets, extrinsic, meta_type::<()>()).into();
+
+		metadata.try_into().expect("test case works")
+	}
+
+	/// Calls of a module, by name, with the names and types of their arguments.
+	type ModuleCalls<'a> = &'a [(&'a str, &'a [(&'a str, &'a str)])];
+
+	/// V12 metadata, without a type registry, with calls of the given modules by their argument
+	/// names and types.
+	fn v12_metadata(modules: &[(&str, u8, ModuleCalls)]) -> Metadata {
+		let decoded = |s: &str| DecodeDifferent::Decoded(s.to_owned());
+		let modules = modules
+			.iter()
+			.map(|(name, index, calls)| v12::ModuleMetadata {
+				name: decoded(name),
+				storage: None,
+				calls: Some(DecodeDifferent::Decoded(
+					calls
+						.iter()
+						.map(|(name, arguments)| v12::FunctionMetadata {
+							name: decoded(name),
+							arguments: DecodeDifferent::Decoded(
+								arguments
+									.iter()
+									.map(|(name, ty)| v12::FunctionArgumentMetadata {
+										name: decoded(name),
+										ty: decoded(ty),
+									})
+									.collect(),
+							),
+							documentation: DecodeDifferent::Decoded(vec![]),
+						})
+						.collect(),
+				)),
+				event: None,
+				constants: DecodeDifferent::Decoded(vec![]),
+				errors: DecodeDifferent::Decoded(vec![]),
+				index: *index,
+			})
+			.collect();
+		let metadata = RuntimeMetadataPrefixed(
+			META_RESERVED,
+			RuntimeMetadata::V12(v12::RuntimeMetadataV12 {
+				modules: DecodeDifferent::Decoded(modules),
+				extrinsic: v12::ExtrinsicMetadata { version: 4, signed_extensions: vec![] },
+			}),
+		);
+
+		metadata.try_into().expect("test case works")
+	}
+
+	/// V12 metadata with a subset of the calls of a Kusama like runtime.
+	fn legacy_metadata() -> Metadata {
+		v12_metadata(&[
+			("System", 0, &[("remark", &[("_remark", "Vec<u8>")])]),
+			(
+				"Balances",
+				5,
+				&[(
+					"transfer",
+					&[
+						("dest", "<T::Lookup as StaticLookup>::Source"),
+						("value", "Compact<T::Balance>"),
+					],
+				)],
+			),
+			(
+				"Utility",
+				24,
+				&[
+					("batch", &[("calls", "Vec<<T as Trait>::Call>")]),
+					("as_derivative", &[("index", "u16"), ("call", "Box<<T as Trait>::Call>")]),
+					("unsupported", &[("weight", "Weight")]),
+				],
+			),
+		])
+	}
+
+	fn transfer(value: u128) -> RuntimeCall {
+		RuntimeCall::Balances(BalancesCall::transfer_keep_alive {
+			dest: MultiAddress::Id(AccountId32([2; 32])),
+			value,
+		})
+	}
+
+	fn transfer_value(value: u128) -> Value {
+		Value::Variant(
+			"transfer_keep_alive".into(),
+			Fields::Named(vec![
+				(
+					"dest".into(),
+					Value::Variant(
+						"Id".into(),
+						Fields::Unnamed(vec![Value::Account([2; 32].into())]),
+					),
+				),
+				("value".into(), Value::Compact(Box::new(Value::UInt(value)))),
+			]),
+		)
+	}
+
+	#[test]
+	fn decode_call_works() {
+		let bytes = transfer(12).encode();
+		let mut input = &bytes[..];
+
+		let call = metadata().decode_call(&mut input).expect("test case works");
+		assert!(input.is_empty());
+		let expected = match transfer_value(12) {
+			Value::Variant(call, Fields::Named(arguments)) => {
+				DecodedCall { pallet: "Balances".into(), call, arguments }
+			}
+			_ => unreachable!(),
+		};
+		assert_eq!(call, expected);
+		assert_eq!(
+			call.arguments[0].1.to_string(),
+			"Id(5C7LYpP2ZH3tpKbvVvwiVe54AapxErdPBbvkYhe6y9ZBkqWt)"
+		);
+		assert_eq!(
+			call.arguments[0]
+				.1
+				.display_with_ss58_format(Ss58AddressFormat::KusamaAccount)
+				.to_string(),
+			format!(
+				"Id({})",
+				sp_core::crypto::AccountId32::from([2; 32])
+					.to_ss58check_with_version(Ss58AddressFormat::KusamaAccount)
+			)
+		);
+		assert_eq!(call.arguments[1].1.to_string(), "12");
+	}
+
+	#[test]
+	fn decode_call_works_with_nested_calls() {
+		let batch = RuntimeCall::Utility(UtilityCall::batch {
+			calls: vec![
+				transfer(1),
+				RuntimeCall::Utility(UtilityCall::remark {
+					remark: vec![7],
+					labels: (true, Some(-1)),
+				}),
+			],
+		});
+		let bytes = batch.encode();
+
+		let call = metadata().decode_call(&mut &bytes[..]).expect("test case works");
+		assert_eq!((call.pallet.as_str(), call.call.as_str()), ("Utility", "batch"));
+		let remark = Value::Variant(
+			"remark".into(),
+			Fields::Named(vec![
+				("remark".into(), Value::Bytes(vec![7])),
+				(
+					"labels".into(),
+					Value::Sequence(vec![
+						Value::Bool(true),
+						Value::Variant("Some".into(), Fields::Unnamed(vec![Value::Int(-1)])),
+					]),
+				),
+			]),
+		);
+		assert_eq!(
+			call.arguments,
+			vec![(
+				"calls".into(),
+				Value::Sequence(vec![
+					Value::Variant("Balances".into(), Fields::Unnamed(vec![transfer_value(1)])),
+					Value::Variant("Utility".into(), Fields::Unnamed(vec![remark])),
+				])
+			)]
+		);
+	}
+
+	#[test]
+	fn decode_call_limits_the_nesting_of_calls() {
+		// `Utility.as_derivative` wrapping `depth` further `as_derivative` calls around a transfer
+		let as_derivative = |depth| {
+			let mut bytes = [26, 1, 0, 0].repeat(depth + 1);
+			bytes.extend(transfer(12).encode());
+			bytes
+		};
+
+		let bytes = as_derivative(100);
+		let call = metadata().decode_call(&mut &bytes[..]).expect("test case works");
+		assert_eq!((call.pallet.as_str(), call.call.as_str()), ("Utility", "as_derivative"));
+
+		// Each nested call is two values deep, its `RuntimeCall` and `UtilityCall` variant
+		for depth in [(MAX_DEPTH / 2) as usize, 100_000].iter().copied() {
+			let bytes = as_derivative(depth);
+			assert!(metadata().decode_call(&mut &bytes[..]).is_err());
+		}
+	}
+
+	#[test]
+	fn decode_call_works_by_type_name_before_v14() {
+		let transfer = [&[5u8, 0][..], &[0], &[2; 32], &scale::Compact(12u128).encode()].concat();
+		let remark = [&[0u8, 0][..], &vec![1u8, 2].encode()].concat();
+		let as_derivative = [&[24u8, 1][..], &3u16.encode(), &transfer].concat();
+		let batch =
+			[&[24u8, 0][..], &scale::Compact(2u32).encode(), &as_derivative, &remark].concat();
+
+		let call = legacy_metadata().decode_call(&mut &batch[..]).expect("test case works");
+		assert_eq!((call.pallet.as_str(), call.call.as_str()), ("Utility", "batch"));
+		// Values are decoded as the type registry would decode them
+		let transfer = Value::Variant(
+			"transfer".into(),
+			Fields::Named(vec![
+				(
+					"dest".into(),
+					Value::Variant(
+						"Id".into(),
+						Fields::Unnamed(vec![Value::Account([2; 32].into())]),
+					),
+				),
+				("value".into(), Value::Compact(Box::new(Value::UInt(12)))),
+			]),
+		);
+		let call_value =
+			|pallet: &str, call| Value::Variant(pallet.into(), Fields::Unnamed(vec![call]));
+		assert_eq!(
+			call.arguments,
+			vec![(
+				"calls".into(),
+				Value::Sequence(vec![
+					call_value(
+						"Utility",
+						Value::Variant(
+							"as_derivative".into(),
+							Fields::Named(vec![
+								("index".into(), Value::UInt(3)),
+								("call".into(), call_value("Balances", transfer)),
+							]),
+						),
+					),
+					call_value(
+						"System",
+						Value::Variant(
+							"remark".into(),
+							Fields::Named(vec![("_remark".into(), Value::Bytes(vec![1, 2]))]),
+						),
+					),
+				])
+			)]
+		);
+
+		// Argument type that is not supported
+		let unsupported = [24u8, 2, 0, 0, 0, 0, 0, 0, 0, 0];
+		assert!(legacy_metadata().decode_call(&mut &unsupported[..]).is_err());
+	}
+
+	#[test]
+	fn decode_call_limits_the_nesting_of_calls_before_v14() {
+		// `Utility.as_derivative` wrapping `depth` further `as_derivative` calls around a remark
+		let as_derivative = |depth| {
+			let mut bytes = [24, 1, 0, 0].repeat(depth + 1);
+			bytes.extend([0, 0, 0]);
+			bytes
+		};
+
+		let bytes = as_derivative(100);
+		let call = legacy_metadata().decode_call(&mut &bytes[..]).expect("test case works");
+		assert_eq!((call.pallet.as_str(), call.call.as_str()), ("Utility", "as_derivative"));
+
+		// Each nested call is two values deep, its `Box` and `Call`
+		for depth in [(MAX_DEPTH / 2) as usize, 100_000].iter().copied() {
+			let bytes = as_derivative(depth);
+			assert!(legacy_metadata().decode_call(&mut &bytes[..]).is_err());
+		}
+	}
+
+	#[test]
+	fn decode_signed_extension_works() {
+		let metadata = metadata();
+		let bytes = [
+			ChargeAssetTxPayment { tip: 1_000, asset_id: Some(1984) }.encode(),
+			transfer(12).encode(),
+		]
+		.concat();
+		let mut input = &bytes[..];
+
+		let extension = metadata
+			.decode_signed_extension("ChargeAssetTxPayment", &mut input)
+			.expect("test case works");
+		assert_eq!(extension.to_string(), "{ tip: 1000, asset_id: Some(1984) }");
+		assert_eq!(input, &transfer(12).encode()[..]);
+
+		assert!(metadata.decode_signed_extension("CheckNonce", &mut &bytes[..]).is_err());
+	}
+
+	#[test]
+	fn decode_call_rejects_invalid_input() {
+		let metadata = metadata();
+		let bytes = transfer(12).encode();
+
+		// Truncated arguments
+		assert!(metadata.decode_call(&mut &bytes[..bytes.len() - 1]).is_err());
+		// Unknown call index
+		assert!(metadata.decode_call(&mut &[4, 0][..]).is_err());
+		// Unknown enum variant of an argument
+		let mut invalid_dest = bytes.clone();
+		invalid_dest[2] = 9;
+		assert!(metadata.decode_call(&mut &invalid_dest[..]).is_err());
+	}
+
+	#[test]
+	fn decoded_values_encode_back_to_the_same_bytes() {
+		let metadata = metadata();
+		let bytes = transfer(1_000_000_000_000).encode();
+
+		let call = metadata.decode_call(&mut &bytes[..]).expect("test case works");
+		let values = call.arguments.into_iter().map(|(_, value)| value).collect();
+		let dynamic_call = crate::DynamicCall::new(call.pallet, call.call, values);
+		let generic_call = dynamic_call.to_generic_call(&metadata).expect("test case works");
+		assert_eq!(codec::Encode::encode(&generic_call), bytes);
+	}
+}
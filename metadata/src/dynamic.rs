@@ -0,0 +1,510 @@
+use crate::{
+	value::{Fields, Value},
+	Metadata,
+};
+use core::convert::TryFrom;
+use scale::{Compact, Encode};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use sp_core::crypto::{AccountId32, Ss58AddressFormat, Ss58Codec};
+use substrate_airgapped::{Error, GenericCall, RawArgs};
+
+/// Call (a.k.a dispatchable) constructed at runtime from the names of its pallet and call, and
+/// the values of its arguments.
+///
+/// The arguments are encoded by walking the type registry of V14 metadata, so any call of any
+/// chain can be constructed without a `PalletCall` struct.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicCall {
+	pallet: String,
+	call: String,
+	arguments: Vec<Value>,
+	ss58_format: Option<Ss58AddressFormat>,
+}
+
+impl DynamicCall {
+	/// Create a `DynamicCall`. `arguments` are given in the order of the call's arguments.
+	pub fn new(pallet: impl Into<String>, call: impl Into<String>, arguments: Vec<Value>) -> Self {
+		DynamicCall { pallet: pallet.into(), call: call.into(), arguments, ss58_format: None }
+	}
+
+	/// Accept 32 byte accounts given as SS58 addresses with the network prefix of the chain.
+	/// Without it, or for an address with another prefix, encoding them errors.
+	pub fn with_ss58_format(self, ss58_format: Ss58AddressFormat) -> Self {
+		Self { ss58_format: Some(ss58_format), ..self }
+	}
+
+	/// Name of the pallet, as it appears in metadata.
+	pub fn pallet(&self) -> &str {
+		&self.pallet
+	}
+
+	/// Name of the call, as it appears in metadata.
+	pub fn call(&self) -> &str {
+		&self.call
+	}
+
+	/// Values of the call's arguments.
+	pub fn arguments(&self) -> &[Value] {
+		&self.arguments
+	}
+
+	/// SCALE encode the arguments according to their types in `metadata`.
+	pub fn encode_args(&self, metadata: &Metadata) -> Result<Vec<u8>, Error> {
+		let types = metadata.types().ok_or("Dynamic calls require V14 metadata or later")?;
+		let call_metadata = metadata.call_metadata(&self.pallet, &self.call)?;
+		if call_metadata.arguments().len() != self.arguments.len() {
+			return Err("Wrong number of call arguments".into());
+		}
+
+		let mut bytes = Vec::new();
+		for (argument, value) in call_metadata.arguments().iter().zip(&self.arguments) {
+			let type_id = argument.type_id().ok_or("Call argument has no type id")?;
+			encode_value(types, type_id, value, self.ss58_format, &mut bytes)?;
+		}
+
+		Ok(bytes)
+	}
+
+	/// Create the `GenericCall`, with the call index and encoded arguments from `metadata`.
+	pub fn to_generic_call(&self, metadata: &Metadata) -> Result<GenericCall<RawArgs>, Error> {
+		let call_index = metadata.call_index(&self.pallet, &self.call)?;
+
+		Ok(GenericCall::new(call_index, RawArgs(self.encode_args(metadata)?)))
+	}
+}
+
+/// SCALE encode `value` as the type with `type_id` in `types`. 32 byte accounts given as SS58
+/// addresses must have the network prefix `ss58_format`.
+pub(crate) fn encode_value(
+	types: &PortableRegistry,
+	type_id: u32,
+	value: &Value,
+	ss58_format: Option<Ss58AddressFormat>,
+	out: &mut Vec<u8>,
+) -> Result<(), Error> {
+	let ty = types.resolve(type_id).ok_or("Type could not be found in the type registry")?;
+	match &ty.type_def {
+		TypeDef::Composite(composite) => match value {
+			Value::Composite(fields) => {
+				encode_fields(types, &composite.fields, fields, ss58_format, out)
+			}
+			// Wrapper types, e.g. `AccountId32([u8; 32])`, can be given as their inner value
+			_ if composite.fields.len() == 1 => {
+				encode_value(types, composite.fields[0].ty.id, value, ss58_format, out)
+			}
+			Value::Sequence(values) => {
+				encode_unnamed(types, &composite.fields, values, ss58_format, out)
+			}
+			_ => Err("Expected a composite value".into()),
+		},
+		TypeDef::Variant(variant) => {
+			let find_variant = |name: &str| {
+				variant
+					.variants
+					.iter()
+					.find(|variant| variant.name == name)
+					.ok_or("Enum variant could not be found in the type registry")
+			};
+			match value {
+				Value::Variant(name, fields) => {
+					let variant = find_variant(name)?;
+					out.push(variant.index);
+					encode_fields(types, &variant.fields, fields, ss58_format, out)
+				}
+				Value::Str(name) => {
+					let variant = find_variant(name)?;
+					out.push(variant.index);
+					encode_unnamed(types, &variant.fields, &[], ss58_format, out)
+				}
+				// A variant given as a single entry map, e.g. `{"Id": "5Grw..."}` in JSON
+				Value::Composite(Fields::Named(named)) if named.len() == 1 => {
+					let (name, inner) = &named[0];
+					let variant = find_variant(name)?;
+					out.push(variant.index);
+					match inner {
+						_ if variant.fields.len() == 1 => {
+							encode_value(types, variant.fields[0].ty.id, inner, ss58_format, out)
+						}
+						Value::Composite(fields) => {
+							encode_fields(types, &variant.fields, fields, ss58_format, out)
+						}
+						Value::Sequence(values) => {
+							encode_unnamed(types, &variant.fields, values, ss58_format, out)
+						}
+						_ => Err("Wrong number of fields".into()),
+					}
+				}
+				_ => Err("Expected an enum variant".into()),
+			}
+		}
+		TypeDef::Sequence(sequence) => {
+			let type_param = sequence.type_param.id;
+			if is_u8(types, type_param) {
+				if let Some(bytes) = bytes(value)? {
+					Compact(u32::try_from(bytes.len())?).encode_to(out);
+					out.extend(bytes);
+					return Ok(());
+				}
+			}
+			match value {
+				Value::Sequence(values) => {
+					Compact(u32::try_from(values.len())?).encode_to(out);
+					values.iter().try_for_each(|value| {
+						encode_value(types, type_param, value, ss58_format, out)
+					})
+				}
+				_ => Err("Expected a sequence".into()),
+			}
+		}
+		TypeDef::Array(array) => {
+			let type_param = array.type_param.id;
+			let len = usize::try_from(array.len)?;
+			if is_u8(types, type_param) {
+				let bytes = match value {
+					// 32 byte accounts can be given as SS58 addresses of the chain
+					Value::Str(ss58) if len == 32 && !ss58.starts_with("0x") => {
+						Some(account_from_ss58(ss58, ss58_format)?.to_vec())
+					}
+					_ => bytes(value)?,
+				};
+				if let Some(bytes) = bytes {
+					if bytes.len() != len {
+						return Err("Wrong number of bytes for array".into());
+					}
+					out.extend(bytes);
+					return Ok(());
+				}
+			}
+			match value {
+				Value::Sequence(values) if values.len() == len => values
+					.iter()
+					.try_for_each(|value| encode_value(types, type_param, value, ss58_format, out)),
+				Value::Sequence(_) => Err("Wrong number of values for array".into()),
+				_ => Err("Expected an array".into()),
+			}
+		}
+		TypeDef::Tuple(tuple) => {
+			let values = match value {
+				Value::Sequence(values) | Value::Composite(Fields::Unnamed(values)) => values,
+				_ => return Err("Expected a tuple".into()),
+			};
+			if values.len() != tuple.fields.len() {
+				return Err("Wrong number of values for tuple".into());
+			}
+			tuple
+				.fields
+				.iter()
+				.zip(values)
+				.try_for_each(|(ty, value)| encode_value(types, ty.id, value, ss58_format, out))
+		}
+		TypeDef::Primitive(primitive) => encode_primitive(primitive, value, out),
+		TypeDef::Compact(compact) => encode_compact(types, compact.type_param.id, value, out),
+		TypeDef::BitSequence(_) => Err("Bit sequences are not supported".into()),
+	}
+}
+
+fn encode_fields(
+	types: &PortableRegistry,
+	fields: &[Field<PortableForm>],
+	values: &Fields,
+	ss58_format: Option<Ss58AddressFormat>,
+	out: &mut Vec<u8>,
+) -> Result<(), Error> {
+	let named = match values {
+		Fields::Named(named) => named,
+		Fields::Unnamed(values) => return encode_unnamed(types, fields, values, ss58_format, out),
+	};
+	if named.len() != fields.len() {
+		return Err("Wrong number of fields".into());
+	}
+
+	for field in fields {
+		let name = field.name.as_deref().ok_or("Expected unnamed fields")?;
+		let (_, value) = named
+			.iter()
+			.find(|(value_name, _)| value_name == name)
+			.ok_or("Field is missing a value")?;
+		encode_value(types, field.ty.id, value, ss58_format, out)?;
+	}
+
+	Ok(())
+}
+
+fn encode_unnamed(
+	types: &PortableRegistry,
+	fields: &[Field<PortableForm>],
+	values: &[Value],
+	ss58_format: Option<Ss58AddressFormat>,
+	out: &mut Vec<u8>,
+) -> Result<(), Error> {
+	if values.len() != fields.len() {
+		return Err("Wrong number of fields".into());
+	}
+
+	fields
+		.iter()
+		.zip(values)
+		.try_for_each(|(field, value)| encode_value(types, field.ty.id, value, ss58_format, out))
+}
+
+/// Parse an SS58 address, which must have the network prefix `ss58_format`.
+fn account_from_ss58(
+	ss58: &str,
+	ss58_format: Option<Ss58AddressFormat>,
+) -> Result<[u8; 32], Error> {
+	let ss58_format =
+		ss58_format.ok_or("SS58 addresses require the network prefix of the chain")?;
+	let (account, format) =
+		AccountId32::from_ss58check_with_version(ss58).map_err(|_| "Expected an SS58 address")?;
+	if format != ss58_format {
+		return Err("SS58 address prefix does not match the chain".into());
+	}
+
+	Ok(account.into())
+}
+
+fn encode_primitive(
+	primitive: &TypeDefPrimitive,
+	value: &Value,
+	out: &mut Vec<u8>,
+) -> Result<(), Error> {
+	match (primitive, value) {
+		(TypeDefPrimitive::Bool, Value::Bool(b)) => b.encode_to(out),
+		(TypeDefPrimitive::Char, Value::Char(c)) => u32::from(*c).encode_to(out),
+		(TypeDefPrimitive::Str, Value::Str(s)) => s.encode_to(out),
+		(TypeDefPrimitive::U8, _) => u8::try_from(uint(value)?)?.encode_to(out),
+		(TypeDefPrimitive::U16, _) => u16::try_from(uint(value)?)?.encode_to(out),
+		(TypeDefPrimitive::U32, _) => u32::try_from(uint(value)?)?.encode_to(out),
+		(TypeDefPrimitive::U64, _) => u64::try_from(uint(value)?)?.encode_to(out),
+		(TypeDefPrimitive::U128, _) => uint(value)?.encode_to(out),
+		(TypeDefPrimitive::I8, _) => i8::try_from(int(value)?)?.encode_to(out),
+		(TypeDefPrimitive::I16, _) => i16::try_from(int(value)?)?.encode_to(out),
+		(TypeDefPrimitive::I32, _) => i32::try_from(int(value)?)?.encode_to(out),
+		(TypeDefPrimitive::I64, _) => i64::try_from(int(value)?)?.encode_to(out),
+		(TypeDefPrimitive::I128, _) => int(value)?.encode_to(out),
+		(TypeDefPrimitive::U256, _) | (TypeDefPrimitive::I256, _) => {
+			return Err("256 bit integers are not supported".into())
+		}
+		_ => return Err("Value does not match primitive type".into()),
+	}
+
+	Ok(())
+}
+
+fn encode_compact(
+	types: &PortableRegistry,
+	type_id: u32,
+	value: &Value,
+	out: &mut Vec<u8>,
+) -> Result<(), Error> {
+	let ty = types.resolve(type_id).ok_or("Type could not be found in the type registry")?;
+	let value = match value {
+		Value::Compact(inner) => inner,
+		value => value,
+	};
+	match &ty.type_def {
+		TypeDef::Primitive(primitive) => {
+			let n = uint(value)?;
+			// The compact encoding only depends on the value, but it still has to fit the type
+			match primitive {
+				TypeDefPrimitive::U8 => Compact(u8::try_from(n)?).encode_to(out),
+				TypeDefPrimitive::U16 => Compact(u16::try_from(n)?).encode_to(out),
+				TypeDefPrimitive::U32 => Compact(u32::try_from(n)?).encode_to(out),
+				TypeDefPrimitive::U64 => Compact(u64::try_from(n)?).encode_to(out),
+				TypeDefPrimitive::U128 => Compact(n).encode_to(out),
+				_ => return Err("Unsupported compact type".into()),
+			}
+
+			Ok(())
+		}
+		// Compact encoded wrapper types, e.g. `Compact<Perbill>`
+		TypeDef::Composite(composite) if composite.fields.len() == 1 => {
+			let value = match value {
+				Value::Composite(Fields::Named(named)) if named.len() == 1 => &named[0].1,
+				Value::Composite(Fields::Unnamed(values)) if values.len() == 1 => &values[0],
+				value => value,
+			};
+			encode_compact(types, composite.fields[0].ty.id, value, out)
+		}
+		_ => Err("Unsupported compact type".into()),
+	}
+}
+
+fn is_u8(types: &PortableRegistry, type_id: u32) -> bool {
+	matches!(
+		types.resolve(type_id).map(|ty| &ty.type_def),
+		Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+	)
+}
+
+/// The bytes of a `Value::Bytes` or `Value::Account`, or of a `Value::Str` with hex.
+fn bytes(value: &Value) -> Result<Option<Vec<u8>>, Error> {
+	match value {
+		Value::Bytes(bytes) => Ok(Some(bytes.clone())),
+		Value::Account(account) => Ok(Some(AsRef::<[u8]>::as_ref(account).to_vec())),
+		Value::Str(s) if s.starts_with("0x") => {
+			Ok(Some(hex::decode(&s[2..]).map_err(|_| "Expected hex with a leading 0x")?))
+		}
+		_ => Ok(None),
+	}
+}
+
+fn uint(value: &Value) -> Result<u128, Error> {
+	match value {
+		Value::UInt(n) => Ok(*n),
+		Value::Int(n) => Ok(u128::try_from(*n)?),
+		Value::Str(s) => Ok(s.parse().map_err(|_| "Expected an unsigned integer")?),
+		_ => Err("Expected an unsigned integer".into()),
+	}
+}
+
+fn int(value: &Value) -> Result<i128, Error> {
+	match value {
+		Value::Int(n) => Ok(*n),
+		Value::UInt(n) => Ok(i128::try_from(*n)?),
+		Value::Str(s) => Ok(s.parse().map_err(|_| "Expected an integer")?),
+		_ => Err("Expected an integer".into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::convert::TryInto;
+	use frame_metadata::v14::{
+		ExtrinsicMetadata, PalletCallMetadata, PalletMetadata, RuntimeMetadataV14,
+	};
+	use scale_info::{meta_type, TypeInfo};
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo)]
+	enum MultiAddress {
+		Id([u8; 32]),
+		Index(#[codec(compact)] u32),
+	}
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo)]
+	struct Remark {
+		remark: Vec<u8>,
+		labels: Vec<(u16, bool)>,
+	}
+
+	// Shaped like the call enum generated by `#[pallet::call]`
+	#[allow(dead_code, non_camel_case_types)]
+	#[derive(TypeInfo)]
+	enum Call {
+		#[codec(index = 3)]
+		transfer_keep_alive {
+			dest: MultiAddress,
+			#[codec(compact)]
+			value: u128,
+		},
+		#[codec(index = 7)]
+		remark_with_labels { remark: Remark, tip: Option<u64> },
+	}
+
+	fn metadata() -> Metadata {
+		let pallet = PalletMetadata {
+			name: "Test",
+			storage: None,
+			calls: Some(PalletCallMetadata { ty: meta_type::<Call>() }),
+			event: None,
+			constants: vec![],
+			error: None,
+			index: 9,
+		};
+		let extrinsic =
+			ExtrinsicMetadata { ty: meta_type::<()>(), version: 4, signed_extensions: vec![] };
+		let metadata: crate::RuntimeMetadataPrefixed =
+			RuntimeMetadataV14::new(vec![pallet], extrinsic, meta_type::<()>()).into();
+
+		metadata.try_into().expect("test case works")
+	}
+
+	#[test]
+	fn dynamic_call_encodes_like_static_types() {
+		let bob = AccountId32::from([2; 32]);
+		let call = DynamicCall::new(
+			"Test",
+			"transfer_keep_alive",
+			vec![
+				Value::Composite(Fields::Named(vec![(
+					"Id".into(),
+					Value::Str(bob.to_ss58check()),
+				)])),
+				Value::Str("1000000000000".into()),
+			],
+		)
+		.with_ss58_format(Ss58AddressFormat::SubstrateAccount);
+
+		let generic_call = call.to_generic_call(&metadata()).expect("test case works");
+		let mut expected = vec![9, 3, 0];
+		expected.extend([2; 32].iter());
+		expected.extend(Compact(1_000_000_000_000u128).encode());
+		assert_eq!(codec::Encode::encode(&generic_call), expected);
+	}
+
+	#[test]
+	fn dynamic_call_encodes_nested_values() {
+		let remark = Value::Composite(Fields::Named(vec![
+			(
+				"labels".into(),
+				Value::Sequence(vec![Value::Sequence(vec![Value::UInt(1), Value::Bool(true)])]),
+			),
+			("remark".into(), Value::Str("0x0102".into())),
+		]));
+		let call = DynamicCall::new(
+			"Test",
+			"remark_with_labels",
+			vec![remark, Value::Variant("Some".into(), Fields::Unnamed(vec![Value::UInt(5)]))],
+		);
+
+		let bytes = call.encode_args(&metadata()).expect("test case works");
+		let expected = ((vec![1u8, 2], vec![(1u16, true)]), Some(5u64)).encode();
+		assert_eq!(bytes, expected);
+	}
+
+	#[test]
+	fn dynamic_call_rejects_mismatched_values() {
+		let metadata = metadata();
+		let transfer =
+			|dest, value| DynamicCall::new("Test", "transfer_keep_alive", vec![dest, value]);
+		let dest = Value::Variant("Index".into(), Fields::Unnamed(vec![Value::UInt(1)]));
+
+		assert!(transfer(dest.clone(), Value::UInt(1)).encode_args(&metadata).is_ok());
+		assert!(transfer(dest.clone(), Value::Int(-1)).encode_args(&metadata).is_err());
+		assert!(transfer(dest, Value::Bool(true)).encode_args(&metadata).is_err());
+		let unknown_variant = Value::Variant("Raw".into(), Fields::Unnamed(vec![]));
+		assert!(transfer(unknown_variant, Value::UInt(1)).encode_args(&metadata).is_err());
+		let short_account =
+			Value::Variant("Id".into(), Fields::Unnamed(vec![Value::Bytes(vec![1])]));
+		assert!(transfer(short_account, Value::UInt(1)).encode_args(&metadata).is_err());
+		assert!(DynamicCall::new("Test", "transfer_keep_alive", vec![])
+			.encode_args(&metadata)
+			.is_err());
+	}
+
+	#[test]
+	fn dynamic_call_checks_the_network_prefix_of_ss58_addresses() {
+		let metadata = metadata();
+		let bob = AccountId32::from([2; 32]);
+		let transfer = |dest: String| {
+			let dest = Value::Variant("Id".into(), Fields::Unnamed(vec![Value::Str(dest)]));
+			DynamicCall::new("Test", "transfer_keep_alive", vec![dest, Value::UInt(12)])
+		};
+		let kusama_bob = bob.to_ss58check_with_version(Ss58AddressFormat::KusamaAccount);
+		let polkadot_bob = bob.to_ss58check_with_version(Ss58AddressFormat::PolkadotAccount);
+
+		let kusama = Ss58AddressFormat::KusamaAccount;
+		let bytes = transfer(kusama_bob.clone())
+			.with_ss58_format(kusama)
+			.encode_args(&metadata)
+			.expect("test case works");
+		assert_eq!(bytes[1..33], [2; 32]);
+		// Polkadot address in a Kusama call
+		assert!(transfer(polkadot_bob).with_ss58_format(kusama).encode_args(&metadata).is_err());
+		// No network prefix to check the address against
+		assert!(transfer(kusama_bob).encode_args(&metadata).is_err());
+	}
+}
@@ -1,20 +1,49 @@
 //! Runtime metadata decoding and lookup support for substrate-airgapped
 #![warn(missing_docs)]
 
-use std::collections::HashMap;
+mod decode;
+mod dynamic;
+mod metadata_hash;
+mod value;
+mod vault;
+
 use core::convert::TryFrom;
-use frame_metadata::{DecodeDifferent, META_RESERVED};
+use frame_metadata::{decode_different::DecodeDifferent, META_RESERVED};
+use scale::Decode;
+use scale_info::{form::PortableForm, Field, Type, TypeDef, TypeDefPrimitive};
+use std::collections::HashMap;
 use substrate_airgapped::{CallIndex, PalletCall};
 
+pub use decode::DecodedCall;
+pub use dynamic::DynamicCall;
 pub use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+pub use metadata_hash::{metadata_hash, ExtraInfo};
+pub use scale_info::PortableRegistry;
+pub use value::{Fields, Value};
+pub use vault::vault_load_metadata;
 
 /// Runtime metadata.
 #[derive(Clone, Debug)]
 pub struct Metadata {
 	modules_with_calls: HashMap<String, ModuleWithCalls>,
+	/// Registry of the types referenced by V14 metadata onwards.
+	types: Option<PortableRegistry>,
+	/// Identifiers of the signed extensions of the runtime, from V11 metadata onwards.
+	signed_extensions: Vec<String>,
+	/// Ids of the types of the extra data of the signed extensions in `types`, by identifier.
+	signed_extension_types: HashMap<String, u32>,
 }
 
 impl Metadata {
+	/// Decode `Metadata` from the bytes of SCALE encoded `RuntimeMetadataPrefixed`, e.g. the
+	/// result of a `state_getMetadata` RPC.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, substrate_airgapped::Error> {
+		let metadata = RuntimeMetadataPrefixed::decode(&mut &bytes[..])
+			.map_err(|_| "Failed to decode runtime metadata")?;
+
+		Metadata::try_from(metadata)
+	}
+
 	/// Returns `ModuleWithCalls`.
 	fn module_with_calls(
 		&self,
@@ -27,21 +56,173 @@ impl Metadata {
 
 	/// Get the `CallIndex` for a `PalletCall` in this `Metadata`
 	pub fn find_call_index<C: PalletCall>(&self) -> Result<CallIndex, substrate_airgapped::Error> {
-		let module_with_calls = self.module_with_calls(C::PALLET)?;
-		let module_index = module_with_calls.index;
-		let call_index = module_with_calls
+		self.call_index(C::PALLET, C::CALL)
+	}
+
+	/// Check that the arguments of a `PalletCall` match the arguments of the call in this
+	/// `Metadata`, i.e. that the call has not changed its signature in the runtime.
+	///
+	/// Before V14 metadata the type names of the arguments are compared. From V14 onwards their
+	/// types are resolved in the type registry and compared with the structure of the type names,
+	/// e.g. `Compact<T::Balance>` matches a compact type and `MultiAddress<AccountId32, u32>` a
+	/// type with that path, while a generic name like `T::Balance` matches any type that is not
+	/// compact.
+	pub fn validate_call<C: PalletCall>(&self) -> Result<(), substrate_airgapped::Error> {
+		let call_metadata = self.call_metadata(C::PALLET, C::CALL)?;
+		let type_matches = |arg: &CallArgument, ty: &str| match (&self.types, arg.type_id) {
+			(Some(types), Some(type_id)) => type_matches_name(types, type_id, ty),
+			_ => arg.ty == ty,
+		};
+		let matches = call_metadata.arguments.len() == C::ARGUMENTS.len()
+			&& call_metadata.arguments.len() == C::ARGUMENT_TYPES.len()
+			&& call_metadata
+				.arguments
+				.iter()
+				.zip(C::ARGUMENTS.iter().zip(C::ARGUMENT_TYPES))
+				.all(|(arg, (name, ty))| arg.name == *name && type_matches(arg, ty));
+		if !matches {
+			return Err("Call arguments do not match runtime metadata".into());
+		}
+
+		Ok(())
+	}
+
+	/// Identifiers of the signed extensions of the runtime, in the order their data is encoded in
+	/// a transaction, e.g. to create a `DynamicExtra` with `Tx::dynamic_extra`. Empty before V11
+	/// metadata.
+	pub fn signed_extensions(&self) -> &[String] {
+		&self.signed_extensions
+	}
+
+	/// Get the `CallMetadata` of a call by its pallet and call name, as they appear in this
+	/// `Metadata`.
+	pub fn call_metadata(
+		&self,
+		pallet: &str,
+		call: &str,
+	) -> Result<&CallMetadata, substrate_airgapped::Error> {
+		self.module_with_calls(pallet)?
 			.calls
-			.get(C::CALL)
-			.ok_or("Call could not be found in module runtime metadata")?;
+			.iter()
+			.find(|call_metadata| call_metadata.name == call)
+			.ok_or_else(|| "Call could not be found in module runtime metadata".into())
+	}
 
-		Ok(CallIndex::new(module_index, *call_index))
+	/// Registry of the types referenced by `CallArgument::type_id`. Only available for V14
+	/// metadata onwards.
+	pub fn types(&self) -> Option<&PortableRegistry> {
+		self.types.as_ref()
+	}
+
+	/// Get the `CallIndex` of a call by its pallet and call name, as they appear in this
+	/// `Metadata`.
+	pub fn call_index(
+		&self,
+		pallet: &str,
+		call: &str,
+	) -> Result<CallIndex, substrate_airgapped::Error> {
+		let module_index = self.module_with_calls(pallet)?.index;
+		let call_metadata = self.call_metadata(pallet, call)?;
+
+		Ok(CallIndex::new(module_index, call_metadata.index))
+	}
+
+	/// Get the pallet name and `CallMetadata` of an encoded call, from its first two bytes.
+	pub fn call_from_bytes(
+		&self,
+		call: &[u8],
+	) -> Result<(&str, &CallMetadata), substrate_airgapped::Error> {
+		match call {
+			[module_index, call_index, ..] => {
+				self.call(&CallIndex::new(*module_index, *call_index))
+			}
+			_ => Err("Call is shorter than a call index".into()),
+		}
+	}
+
+	/// Get the pallet name and `CallMetadata` of the call at `call_index`.
+	pub fn call(
+		&self,
+		call_index: &CallIndex,
+	) -> Result<(&str, &CallMetadata), substrate_airgapped::Error> {
+		let module_with_calls = self
+			.modules_with_calls
+			.values()
+			.find(|module| module.index == call_index.module_index())
+			.ok_or("Module index could not be found in runtime metadata")?;
+		let call_metadata = module_with_calls
+			.calls
+			.iter()
+			.find(|call_metadata| call_metadata.index == call_index.call_index())
+			.ok_or("Call index could not be found in module runtime metadata")?;
+
+		Ok((&module_with_calls.name, call_metadata))
 	}
 }
 
 #[derive(Clone, Debug)]
 struct ModuleWithCalls {
+	name: String,
+	index: u8,
+	calls: Vec<CallMetadata>,
+}
+
+/// Metadata of a call (a.k.a dispatchable).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallMetadata {
+	name: String,
 	index: u8,
-	calls: HashMap<String, u8>,
+	arguments: Vec<CallArgument>,
+	documentation: Vec<String>,
+}
+
+impl CallMetadata {
+	/// Name of the call, as it appears in metadata.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Index of the call in its pallet.
+	pub fn index(&self) -> u8 {
+		self.index
+	}
+
+	/// Arguments of the call, in the order they are encoded.
+	pub fn arguments(&self) -> &[CallArgument] {
+		&self.arguments
+	}
+
+	/// Lines of the call's documentation, as written in the runtime's source.
+	pub fn documentation(&self) -> &[String] {
+		&self.documentation
+	}
+}
+
+/// Name and type of a call argument.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallArgument {
+	name: String,
+	ty: String,
+	type_id: Option<u32>,
+}
+
+impl CallArgument {
+	/// Name of the argument.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Name of the argument's type, as it appears in the runtime's source (e.g.
+	/// `Compact<T::Balance>`). For V14 metadata, this falls back to the path of the type in the
+	/// type registry if the source name is not included.
+	pub fn ty(&self) -> &str {
+		&self.ty
+	}
+
+	/// Id of the argument's type in `Metadata::types`. Only available for V14 metadata onwards.
+	pub fn type_id(&self) -> Option<u32> {
+		self.type_id
+	}
 }
 
 impl TryFrom<RuntimeMetadataPrefixed> for Metadata {
@@ -51,33 +232,342 @@ impl TryFrom<RuntimeMetadataPrefixed> for Metadata {
 		if metadata.0 != META_RESERVED {
 			return Err("Failed to convert".into());
 		}
-		let meta = match metadata.1 {
-			RuntimeMetadata::V12(meta) => meta,
-			_ => return Err("Invalid metadata version".into()),
-		};
+		let (modules_with_calls, types, signed_extensions, signed_extension_types) =
+			match metadata.1 {
+				// Before V12, the index of a module is its position amongst the modules that have
+				// calls or events, which are the modules included in the outer `Call` and `Event`.
+				RuntimeMetadata::V9(meta) => (
+					legacy_modules_with_calls!(meta.modules, |_module, position| position),
+					None,
+					vec![],
+					HashMap::new(),
+				),
+				RuntimeMetadata::V10(meta) => (
+					legacy_modules_with_calls!(meta.modules, |_module, position| position),
+					None,
+					vec![],
+					HashMap::new(),
+				),
+				RuntimeMetadata::V11(meta) => (
+					legacy_modules_with_calls!(meta.modules, |_module, position| position),
+					None,
+					legacy_signed_extensions(meta.extrinsic.signed_extensions)?,
+					HashMap::new(),
+				),
+				RuntimeMetadata::V12(meta) => (
+					legacy_modules_with_calls!(meta.modules),
+					None,
+					legacy_signed_extensions(meta.extrinsic.signed_extensions)?,
+					HashMap::new(),
+				),
+				RuntimeMetadata::V13(meta) => (
+					legacy_modules_with_calls!(meta.modules),
+					None,
+					legacy_signed_extensions(meta.extrinsic.signed_extensions)?,
+					HashMap::new(),
+				),
+				RuntimeMetadata::V14(meta) => {
+					let pallets = meta.pallets.iter().map(|pallet| {
+						(&pallet.name, pallet.index, pallet.calls.as_ref().map(|calls| calls.ty.id))
+					});
+					let signed_extensions = meta
+						.extrinsic
+						.signed_extensions
+						.iter()
+						.map(|extension| (extension.identifier.clone(), extension.ty.id));
+					(
+						registry_modules_with_calls(&meta.types, pallets)?,
+						Some(meta.types),
+						signed_extensions.clone().map(|(identifier, _)| identifier).collect(),
+						signed_extensions.collect(),
+					)
+				}
+				RuntimeMetadata::V15(meta) => {
+					let pallets = meta.pallets.iter().map(|pallet| {
+						(&pallet.name, pallet.index, pallet.calls.as_ref().map(|calls| calls.ty.id))
+					});
+					let signed_extensions = meta
+						.extrinsic
+						.signed_extensions
+						.iter()
+						.map(|extension| (extension.identifier.clone(), extension.ty.id));
+					(
+						registry_modules_with_calls(&meta.types, pallets)?,
+						Some(meta.types),
+						signed_extensions.clone().map(|(identifier, _)| identifier).collect(),
+						signed_extensions.collect(),
+					)
+				}
+				_ => return Err("Invalid metadata version".into()),
+			};
+
+		Ok(Metadata { modules_with_calls, types, signed_extensions, signed_extension_types })
+	}
+}
 
+/// Collect the `ModuleWithCalls` from the modules of pre V14 metadata. The modules of each version
+/// have the same shape, but are distinct types.
+///
+/// The index of each module is read from `module.index`, unless an expression computing it from
+/// the module and its position amongst the modules with calls or events is given.
+macro_rules! legacy_modules_with_calls {
+	($modules:expr) => {
+		legacy_modules_with_calls!($modules, |module, _position| module.index)
+	};
+	($modules:expr, |$module:ident, $position:ident| $index:expr) => {{
 		let mut modules_with_calls = HashMap::new();
-		for module in convert(meta.modules)?.into_iter() {
+		let mut position: usize = 0;
+		for module in convert($modules)?.into_iter() {
+			let module_index = {
+				let $module = &module;
+				let $position = u8::try_from(position)?;
+				$index
+			};
+			if module.calls.is_some() || module.event.is_some() {
+				position += 1;
+			}
+
 			if let Some(calls_meta) = module.calls {
 				let calls = convert(calls_meta)?
 					.into_iter()
 					.enumerate()
 					.map(|(index, call)| {
-						let call_name = convert(call.name)?;
-						let index = u8::try_from(index)?;
+						let arguments = convert(call.arguments)?
+							.into_iter()
+							.map(|arg| {
+								Ok(CallArgument {
+									name: convert(arg.name)?,
+									ty: convert(arg.ty)?,
+									type_id: None,
+								})
+							})
+							.collect::<Result<Vec<CallArgument>, substrate_airgapped::Error>>()?;
 
-						Ok((call_name, index))
+						Ok(CallMetadata {
+							name: convert(call.name)?,
+							index: u8::try_from(index)?,
+							arguments,
+							documentation: convert(call.documentation)?,
+						})
 					})
-					.collect::<Result<HashMap<String, u8>, substrate_airgapped::Error>>()?;
+					.collect::<Result<Vec<CallMetadata>, substrate_airgapped::Error>>()?;
 
-				let module_name = convert(module.name)?.to_string();
-				modules_with_calls
-					.insert(module_name.clone(), ModuleWithCalls { index: module.index, calls });
+				let module_name = convert(module.name)?;
+				modules_with_calls.insert(
+					module_name.clone(),
+					ModuleWithCalls { name: module_name, index: module_index, calls },
+				);
 			}
 		}
 
-		Ok(Metadata { modules_with_calls })
+		modules_with_calls
+	}};
+}
+use legacy_modules_with_calls;
+
+/// Convert the identifiers of the signed extensions of pre V14 metadata.
+fn legacy_signed_extensions(
+	signed_extensions: Vec<DecodeDifferent<&'static str, String>>,
+) -> Result<Vec<String>, substrate_airgapped::Error> {
+	signed_extensions.into_iter().map(convert).collect()
+}
+
+/// Collect the `ModuleWithCalls` from the name, index and call type of the pallets of V14 metadata
+/// onwards, whose pallets have the same shape for each version but are distinct types. The calls
+/// of a pallet are the variants of its call enum in the type registry.
+fn registry_modules_with_calls<'a>(
+	types: &PortableRegistry,
+	pallets: impl Iterator<Item = (&'a String, u8, Option<u32>)>,
+) -> Result<HashMap<String, ModuleWithCalls>, substrate_airgapped::Error> {
+	let mut modules_with_calls = HashMap::new();
+	for (name, index, calls_ty) in pallets {
+		if let Some(calls_ty) = calls_ty {
+			let calls_ty = types
+				.resolve(calls_ty)
+				.ok_or("Call type could not be found in the type registry")?;
+			let variants = match &calls_ty.type_def {
+				TypeDef::Variant(variant) => &variant.variants,
+				_ => return Err("Call type is not an enum".into()),
+			};
+
+			let calls = variants
+				.iter()
+				.map(|variant| CallMetadata {
+					name: variant.name.clone(),
+					index: variant.index,
+					arguments: variant
+						.fields
+						.iter()
+						.map(|field| CallArgument {
+							name: field.name.clone().unwrap_or_default(),
+							ty: v14_type_name(types, field),
+							type_id: Some(field.ty.id),
+						})
+						.collect(),
+					documentation: variant.docs.clone(),
+				})
+				.collect();
+
+			modules_with_calls
+				.insert(name.clone(), ModuleWithCalls { name: name.clone(), index, calls });
+		}
 	}
+
+	Ok(modules_with_calls)
+}
+
+/// Name of the type of a V14 field, as it appears in the runtime's source or else its path in
+/// the type registry.
+fn v14_type_name(types: &PortableRegistry, field: &Field<PortableForm>) -> String {
+	field.type_name.clone().unwrap_or_else(|| {
+		types.resolve(field.ty.id).map(|ty| ty.path.segments.join("::")).unwrap_or_default()
+	})
+}
+
+/// Whether the type `type_id` in the registry has the structure of the type name `name`, as it
+/// appears in metadata before V14. Wrappers, arrays, tuples and primitives are matched with the
+/// resolved type and other concrete names with its path, while a generic name matches any type
+/// that is not compact, as the compact encoding of a V14 argument is part of its type rather than
+/// of its type name.
+fn type_matches_name(types: &PortableRegistry, type_id: u32, name: &str) -> bool {
+	let ty = match types.resolve(type_id) {
+		Some(ty) => ty,
+		None => return false,
+	};
+	let name = name.trim();
+	let wrapped = |wrapper: &str| {
+		name.strip_prefix(wrapper)
+			.and_then(|name| name.strip_prefix('<'))
+			.and_then(|name| name.strip_suffix('>'))
+	};
+
+	if let Some(inner) = wrapped("Compact") {
+		return match &ty.type_def {
+			TypeDef::Compact(compact) => type_matches_name(types, compact.type_param.id, inner),
+			_ => false,
+		};
+	}
+	if let Some(inner) = wrapped("Box") {
+		return type_matches_name(types, type_id, inner);
+	}
+	if let Some(inner) = wrapped("Vec") {
+		return match &ty.type_def {
+			TypeDef::Sequence(sequence) => type_matches_name(types, sequence.type_param.id, inner),
+			_ => false,
+		};
+	}
+	if let Some(inner) = wrapped("Option") {
+		return ty.path.segments == ["Option"]
+			&& ty
+				.type_params
+				.first()
+				.and_then(|param| param.ty)
+				.is_some_and(|param| type_matches_name(types, param.id, inner));
+	}
+	if let Some((inner, len)) = name
+		.strip_prefix('[')
+		.and_then(|name| name.strip_suffix(']'))
+		.and_then(|name| name.rsplit_once(';'))
+	{
+		return match &ty.type_def {
+			TypeDef::Array(array) => {
+				len.trim().parse() == Ok(array.len)
+					&& type_matches_name(types, array.type_param.id, inner)
+			}
+			_ => false,
+		};
+	}
+	if let Some(inner) = name.strip_prefix('(').and_then(|name| name.strip_suffix(')')) {
+		let names = split_top_level(inner);
+		return match &ty.type_def {
+			TypeDef::Tuple(tuple) => {
+				tuple.fields.len() == names.len()
+					&& tuple
+						.fields
+						.iter()
+						.zip(names)
+						.all(|(field, name)| type_matches_name(types, field.id, name))
+			}
+			_ => false,
+		};
+	}
+
+	let primitive = match name {
+		"bool" => TypeDefPrimitive::Bool,
+		"char" => TypeDefPrimitive::Char,
+		"str" | "String" => TypeDefPrimitive::Str,
+		"u8" => TypeDefPrimitive::U8,
+		"u16" => TypeDefPrimitive::U16,
+		"u32" => TypeDefPrimitive::U32,
+		"u64" => TypeDefPrimitive::U64,
+		"u128" => TypeDefPrimitive::U128,
+		"u256" => TypeDefPrimitive::U256,
+		"i8" => TypeDefPrimitive::I8,
+		"i16" => TypeDefPrimitive::I16,
+		"i32" => TypeDefPrimitive::I32,
+		"i64" => TypeDefPrimitive::I64,
+		"i128" => TypeDefPrimitive::I128,
+		"i256" => TypeDefPrimitive::I256,
+		_ if is_generic(name) => return !matches!(ty.type_def, TypeDef::Compact(_)),
+		_ => return path_matches_name(types, ty, name),
+	};
+
+	ty.type_def == TypeDef::Primitive(primitive)
+}
+
+/// Whether the type name refers to a type parameter, e.g. `T::Balance` or `AccountIdLookupOf<T>`,
+/// whose type is only known to the runtime. Type parameters are named by a single capital
+/// letter, as they are in FRAME pallets.
+fn is_generic(name: &str) -> bool {
+	name.split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+		.any(|ident| ident.len() == 1 && ident.starts_with(|c: char| c.is_ascii_uppercase()))
+}
+
+/// Whether the path of the type `ty` ends with the path of the concrete type name `name`, e.g.
+/// `MultiAddress<AccountId32, u32>` matches `sp_runtime::multiaddress::MultiAddress` if its
+/// type parameters match `AccountId32` and `u32`.
+fn path_matches_name(types: &PortableRegistry, ty: &Type<PortableForm>, name: &str) -> bool {
+	let (path, params) = match name.split_once('<') {
+		Some((path, params)) => match params.strip_suffix('>') {
+			Some(params) => (path, split_top_level(params)),
+			None => return false,
+		},
+		None => (name, Vec::new()),
+	};
+	let segments: Vec<_> = path.split("::").map(str::trim).collect();
+	if segments.iter().any(|segment| segment.is_empty())
+		|| ty.path.segments.len() < segments.len()
+		|| !ty.path.segments[ty.path.segments.len() - segments.len()..].iter().eq(&segments)
+	{
+		return false;
+	}
+
+	params.is_empty()
+		|| params.len() == ty.type_params.len()
+			&& ty.type_params.iter().zip(params).all(|(param, name)| {
+				param.ty.is_some_and(|param| type_matches_name(types, param.id, name))
+			})
+}
+
+/// Split the elements of a tuple type name at the commas that are not nested in another type.
+fn split_top_level(names: &str) -> Vec<&str> {
+	let (mut depth, mut start, mut elements) = (0, 0, Vec::new());
+	for (i, c) in names.char_indices() {
+		match c {
+			'<' | '(' | '[' => depth += 1,
+			'>' | ')' | ']' => depth -= 1,
+			',' if depth == 0 => {
+				elements.push(&names[start..i]);
+				start = i + 1;
+			}
+			_ => {}
+		}
+	}
+	if !names[start..].trim().is_empty() {
+		elements.push(&names[start..]);
+	}
+
+	elements
 }
 
 fn convert<B: 'static, O: 'static>(
@@ -88,3 +578,463 @@ fn convert<B: 'static, O: 'static>(
 		_ => Err("Expected decoded".into()),
 	}
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use crate::decode::decode_value;
+	use codec::Encode as _;
+	use frame_metadata::{
+		v11::{self, RuntimeMetadataV11},
+		v13::{self, RuntimeMetadataV13},
+		v14::{
+			ExtrinsicMetadata, PalletCallMetadata, PalletMetadata, RuntimeMetadataV14,
+			SignedExtensionMetadata,
+		},
+		v8::RuntimeMetadataV8,
+	};
+	use scale::Encode;
+	use scale_info::{meta_type, TypeInfo};
+	use sp_core::H256;
+	use substrate_airgapped::{
+		balances::Transfer, with_runtime, Chain, GenericCall, KusamaRuntime, MortalConfig,
+		Mortality, RawArgs, Tx, TxConfig,
+	};
+
+	/// Bytes of the V15 `RuntimeMetadataPrefixed` of a live runtime, e.g. `rococo`, as fetched
+	/// with the `Metadata_metadata_at_version` runtime API.
+	pub(crate) fn fixture(name: &str) -> Vec<u8> {
+		let path = format!("{}/fixtures/{}_metadata_v15.scale", env!("CARGO_MANIFEST_DIR"), name);
+
+		std::fs::read(path).expect("test case works")
+	}
+
+	/// The chains of which `fixtures` has the metadata of a live runtime, with its spec and
+	/// transaction version. These runtimes predate `CheckMetadataHash`, which their chains added
+	/// as the last signed extension afterwards.
+	const FIXTURE_CHAINS: [(&str, Chain, u32, u32); 3] = [
+		("polkadot", Chain::Polkadot, 1_001_002, 25),
+		("kusama", Chain::Kusama, 1_001_002, 25),
+		("rococo", Chain::Rococo, 1_006_002, 24),
+	];
+
+	// Shaped like the call enum generated by `#[pallet::call]`
+	#[allow(dead_code, non_camel_case_types)]
+	#[derive(TypeInfo)]
+	enum BalancesCall {
+		#[codec(index = 0)]
+		transfer_allow_death {
+			dest: [u8; 32],
+			#[codec(compact)]
+			value: u128,
+		},
+		/// Same as `transfer_allow_death`, but checks that the sender is kept alive.
+		#[codec(index = 3)]
+		transfer_keep_alive { dest: [u8; 32], value: u128 },
+	}
+
+	fn v13_metadata() -> RuntimeMetadataPrefixed {
+		let transfer = v13::FunctionMetadata {
+			name: DecodeDifferent::Decoded("transfer".into()),
+			arguments: DecodeDifferent::Decoded(vec![
+				v13::FunctionArgumentMetadata {
+					name: DecodeDifferent::Decoded("dest".into()),
+					ty: DecodeDifferent::Decoded("<T::Lookup as StaticLookup>::Source".into()),
+				},
+				v13::FunctionArgumentMetadata {
+					name: DecodeDifferent::Decoded("value".into()),
+					ty: DecodeDifferent::Decoded("Compact<T::Balance>".into()),
+				},
+			]),
+			documentation: DecodeDifferent::Decoded(vec![
+				" Transfer some liquid free balance.".into()
+			]),
+		};
+		let balances = v13::ModuleMetadata {
+			name: DecodeDifferent::Decoded("Balances".into()),
+			storage: None,
+			calls: Some(DecodeDifferent::Decoded(vec![transfer])),
+			event: None,
+			constants: DecodeDifferent::Decoded(vec![]),
+			errors: DecodeDifferent::Decoded(vec![]),
+			index: 5,
+		};
+
+		RuntimeMetadataPrefixed(
+			META_RESERVED,
+			RuntimeMetadata::V13(RuntimeMetadataV13 {
+				modules: DecodeDifferent::Decoded(vec![balances]),
+				extrinsic: v13::ExtrinsicMetadata {
+					version: 4,
+					signed_extensions: vec![
+						DecodeDifferent::Decoded("CheckSpecVersion".into()),
+						DecodeDifferent::Decoded("CheckNonce".into()),
+					],
+				},
+			}),
+		)
+	}
+
+	fn v11_module(name: &str, calls: &[&str], has_event: bool) -> v11::ModuleMetadata {
+		let calls = calls
+			.iter()
+			.map(|call| v11::FunctionMetadata {
+				name: DecodeDifferent::Decoded((*call).into()),
+				arguments: DecodeDifferent::Decoded(vec![]),
+				documentation: DecodeDifferent::Decoded(vec![]),
+			})
+			.collect::<Vec<_>>();
+
+		v11::ModuleMetadata {
+			name: DecodeDifferent::Decoded(name.into()),
+			storage: None,
+			calls: if calls.is_empty() { None } else { Some(DecodeDifferent::Decoded(calls)) },
+			event: if has_event { Some(DecodeDifferent::Decoded(vec![])) } else { None },
+			constants: DecodeDifferent::Decoded(vec![]),
+			errors: DecodeDifferent::Decoded(vec![]),
+		}
+	}
+
+	fn v11_metadata() -> RuntimeMetadataPrefixed {
+		let modules = vec![
+			v11_module("System", &["remark"], true),
+			v11_module("RandomnessCollectiveFlip", &[], false),
+			v11_module("Indices", &[], true),
+			v11_module("Balances", &["transfer", "set_balance"], true),
+		];
+
+		RuntimeMetadataPrefixed(
+			META_RESERVED,
+			RuntimeMetadata::V11(RuntimeMetadataV11 {
+				modules: DecodeDifferent::Decoded(modules),
+				extrinsic: v11::ExtrinsicMetadata { version: 4, signed_extensions: vec![] },
+			}),
+		)
+	}
+
+	fn v14_metadata() -> RuntimeMetadataPrefixed {
+		v14_metadata_with_calls::<BalancesCall>()
+	}
+
+	/// V14 metadata with `Call` as the call enum of the `Balances` pallet.
+	fn v14_metadata_with_calls<Call: TypeInfo + 'static>() -> RuntimeMetadataPrefixed {
+		let balances = PalletMetadata {
+			name: "Balances",
+			storage: None,
+			calls: Some(PalletCallMetadata { ty: meta_type::<Call>() }),
+			event: None,
+			constants: vec![],
+			error: None,
+			index: 4,
+		};
+		let signed_extension = |identifier| SignedExtensionMetadata {
+			identifier,
+			ty: meta_type::<()>(),
+			additional_signed: meta_type::<()>(),
+		};
+		let extrinsic = ExtrinsicMetadata {
+			ty: meta_type::<()>(),
+			version: 4,
+			signed_extensions: vec![
+				signed_extension("CheckNonZeroSender"),
+				signed_extension("CheckMortality"),
+			],
+		};
+
+		RuntimeMetadataV14::new(vec![balances], extrinsic, meta_type::<()>()).into()
+	}
+
+	#[test]
+	fn v11_call_index_works() {
+		let metadata = Metadata::from_bytes(&v11_metadata().encode()).expect("test case works");
+
+		assert_eq!(
+			metadata.call_index("System", "remark").expect("test case works"),
+			CallIndex::new(0, 0)
+		);
+		// Modules without calls or events do not count towards the module index
+		let call_index = metadata.call_index("Balances", "set_balance").expect("test case works");
+		assert_eq!(call_index, CallIndex::new(2, 1));
+		let (pallet, call) = metadata.call(&call_index).expect("test case works");
+		assert_eq!((pallet, call.name()), ("Balances", "set_balance"));
+
+		assert!(metadata.call_index("Indices", "transfer").is_err());
+	}
+
+	#[test]
+	fn call_from_bytes_works() {
+		let v13 = Metadata::from_bytes(&v13_metadata().encode()).expect("test case works");
+		let (pallet, call) = v13.call_from_bytes(&[5, 0, 1, 2]).expect("test case works");
+		assert_eq!((pallet, call.name()), ("Balances", "transfer"));
+		assert_eq!(call.documentation(), [" Transfer some liquid free balance."]);
+
+		let v14 = Metadata::from_bytes(&v14_metadata().encode()).expect("test case works");
+		let (pallet, call) = v14.call_from_bytes(&[4, 3]).expect("test case works");
+		assert_eq!((pallet, call.name()), ("Balances", "transfer_keep_alive"));
+		assert_eq!(
+			call.documentation(),
+			["Same as `transfer_allow_death`, but checks that the sender is kept alive."]
+		);
+
+		assert!(v14.call_from_bytes(&[4]).is_err());
+		assert!(v14.call_from_bytes(&[4, 1]).is_err());
+	}
+
+	#[test]
+	fn v13_call_index_works() {
+		let metadata = Metadata::from_bytes(&v13_metadata().encode()).expect("test case works");
+
+		let call_index = metadata.call_index("Balances", "transfer").expect("test case works");
+		assert_eq!(call_index, CallIndex::new(5, 0));
+		let (pallet, call) = metadata.call(&call_index).expect("test case works");
+		assert_eq!(pallet, "Balances");
+		assert_eq!(call.arguments()[1].ty(), "Compact<T::Balance>");
+		assert_eq!(call.arguments()[1].type_id(), None);
+		assert!(metadata.types().is_none());
+		assert_eq!(metadata.signed_extensions(), ["CheckSpecVersion", "CheckNonce"]);
+	}
+
+	#[test]
+	fn validate_call_works() {
+		let metadata = Metadata::from_bytes(&v13_metadata().encode()).expect("test case works");
+		assert!(metadata.validate_call::<Transfer<KusamaRuntime>>().is_ok());
+
+		struct OldTransfer;
+		impl PalletCall for OldTransfer {
+			const CALL: &'static str = "transfer";
+			const PALLET: &'static str = "Balances";
+			const ARGUMENTS: &'static [&'static str] = &["dest", "value", "keep_alive"];
+			const ARGUMENT_TYPES: &'static [&'static str] =
+				&["<T::Lookup as StaticLookup>::Source", "Compact<T::Balance>", "bool"];
+		}
+		assert!(metadata.validate_call::<OldTransfer>().is_err());
+
+		// Only the type of `value` differs
+		struct UncompactTransfer;
+		impl PalletCall for UncompactTransfer {
+			const CALL: &'static str = "transfer";
+			const PALLET: &'static str = "Balances";
+			const ARGUMENTS: &'static [&'static str] = &["dest", "value"];
+			const ARGUMENT_TYPES: &'static [&'static str] =
+				&["<T::Lookup as StaticLookup>::Source", "T::Balance"];
+		}
+		assert!(metadata.validate_call::<UncompactTransfer>().is_err());
+	}
+
+	#[test]
+	fn v14_validate_call_works() {
+		#[allow(dead_code, non_camel_case_types)]
+		#[derive(TypeInfo)]
+		enum CompactCall {
+			transfer {
+				dest: [u8; 32],
+				#[codec(compact)]
+				value: u128,
+			},
+		}
+		#[allow(dead_code, non_camel_case_types)]
+		#[derive(TypeInfo)]
+		enum UncompactCall {
+			transfer { dest: [u8; 32], value: u128 },
+		}
+		let compact = Metadata::from_bytes(&v14_metadata_with_calls::<CompactCall>().encode())
+			.expect("test case works");
+		let uncompact = Metadata::from_bytes(&v14_metadata_with_calls::<UncompactCall>().encode())
+			.expect("test case works");
+
+		// The type names of V14 arguments do not include `Compact`, so the resolved types are
+		// compared
+		assert_eq!(
+			compact.call_metadata("Balances", "transfer").map(|call| call.arguments()[1].ty()),
+			Ok("u128")
+		);
+		assert!(compact.validate_call::<Transfer<KusamaRuntime>>().is_ok());
+		assert!(uncompact.validate_call::<Transfer<KusamaRuntime>>().is_err());
+
+		struct ConcreteTransfer;
+		impl PalletCall for ConcreteTransfer {
+			const CALL: &'static str = "transfer";
+			const PALLET: &'static str = "Balances";
+			const ARGUMENTS: &'static [&'static str] = &["dest", "value"];
+			const ARGUMENT_TYPES: &'static [&'static str] = &["[u8; 32]", "Compact<u128>"];
+		}
+		assert!(compact.validate_call::<ConcreteTransfer>().is_ok());
+		assert!(uncompact.validate_call::<ConcreteTransfer>().is_err());
+
+		struct SmallerTransfer;
+		impl PalletCall for SmallerTransfer {
+			const CALL: &'static str = "transfer";
+			const PALLET: &'static str = "Balances";
+			const ARGUMENTS: &'static [&'static str] = &["dest", "value"];
+			const ARGUMENT_TYPES: &'static [&'static str] = &["[u8; 16]", "Compact<u64>"];
+		}
+		assert!(compact.validate_call::<SmallerTransfer>().is_err());
+	}
+
+	#[test]
+	fn v14_validate_call_rejects_arguments_of_another_type() {
+		#[allow(dead_code)]
+		#[derive(TypeInfo)]
+		struct AccountId32([u8; 32]);
+		#[allow(dead_code)]
+		#[derive(TypeInfo)]
+		enum MultiAddress<AccountId, AccountIndex> {
+			Id(AccountId),
+			Index(#[codec(compact)] AccountIndex),
+		}
+		#[allow(dead_code, non_camel_case_types)]
+		#[derive(TypeInfo)]
+		enum Call {
+			transfer {
+				dest: MultiAddress<AccountId32, u32>,
+				#[codec(compact)]
+				value: u128,
+			},
+		}
+		let metadata = Metadata::from_bytes(&v14_metadata_with_calls::<Call>().encode())
+			.expect("test case works");
+
+		let call = metadata.call_metadata("Balances", "transfer").expect("test case works");
+		let dest = call.arguments()[0].type_id().expect("test case works");
+		let types = metadata.types().expect("test case works");
+		assert!(type_matches_name(types, dest, "MultiAddress<AccountId32, u32>"));
+		assert!(type_matches_name(types, dest, "MultiAddress"));
+		assert!(type_matches_name(types, dest, "AccountIdLookupOf<T>"));
+		// Concrete names of another type are rejected rather than matched as unknown names
+		assert!(!type_matches_name(types, dest, "AccountId32"));
+		assert!(!type_matches_name(types, dest, "MultiAddress<AccountId32, u64>"));
+		assert!(!type_matches_name(types, dest, "MultiSigner"));
+
+		struct SignerTransfer;
+		impl PalletCall for SignerTransfer {
+			const CALL: &'static str = "transfer";
+			const PALLET: &'static str = "Balances";
+			const ARGUMENTS: &'static [&'static str] = &["dest", "value"];
+			const ARGUMENT_TYPES: &'static [&'static str] = &["MultiSigner", "Compact<T::Balance>"];
+		}
+		assert!(metadata.validate_call::<Transfer<KusamaRuntime>>().is_ok());
+		assert!(metadata.validate_call::<SignerTransfer>().is_err());
+	}
+
+	#[test]
+	fn v14_call_index_works() {
+		let metadata = Metadata::from_bytes(&v14_metadata().encode()).expect("test case works");
+
+		assert_eq!(
+			metadata.call_index("Balances", "transfer_allow_death").expect("test case works"),
+			CallIndex::new(4, 0)
+		);
+		// Call indexes are taken from the variant index, not the position of the call
+		let call_index =
+			metadata.call_index("Balances", "transfer_keep_alive").expect("test case works");
+		assert_eq!(call_index, CallIndex::new(4, 3));
+
+		let (pallet, call) = metadata.call(&call_index).expect("test case works");
+		assert_eq!(pallet, "Balances");
+		assert_eq!(call.name(), "transfer_keep_alive");
+		let arguments: Vec<_> = call.arguments().iter().map(|arg| (arg.name(), arg.ty())).collect();
+		assert_eq!(arguments, vec![("dest", "[u8; 32]"), ("value", "u128")]);
+
+		let types = metadata.types().expect("test case works");
+		let value_type_id = call.arguments()[1].type_id().expect("test case works");
+		assert_eq!(
+			types.resolve(value_type_id).expect("test case works").type_def,
+			scale_info::TypeDef::Primitive(scale_info::TypeDefPrimitive::U128)
+		);
+		assert_eq!(metadata.signed_extensions(), ["CheckNonZeroSender", "CheckMortality"]);
+	}
+
+	#[test]
+	fn unsupported_version_errors() {
+		let metadata = RuntimeMetadataPrefixed(
+			META_RESERVED,
+			RuntimeMetadata::V8(RuntimeMetadataV8 { modules: DecodeDifferent::Decoded(vec![]) }),
+		);
+
+		assert!(Metadata::try_from(metadata).is_err());
+	}
+
+	struct TransferAllowDeath;
+	impl PalletCall for TransferAllowDeath {
+		const CALL: &'static str = "transfer_allow_death";
+		const PALLET: &'static str = "Balances";
+		const ARGUMENTS: &'static [&'static str] = &["dest", "value"];
+		const ARGUMENT_TYPES: &'static [&'static str] =
+			&["<T::Lookup as StaticLookup>::Source", "Compact<T::Balance>"];
+	}
+
+	#[test]
+	fn v15_metadata_of_live_runtimes_works() {
+		for (name, chain, _, _) in FIXTURE_CHAINS.iter() {
+			let metadata = Metadata::from_bytes(&fixture(name)).expect("test case works");
+
+			let chain_extensions = chain.signed_extensions();
+			let (last, signed_extensions) = chain_extensions.split_last().expect("test case works");
+			assert_eq!(*last, "CheckMetadataHash");
+			assert_eq!(metadata.signed_extensions(), signed_extensions);
+			let call_index =
+				metadata.call_index("Balances", "transfer_allow_death").expect("test case works");
+			let (pallet, call) = metadata.call(&call_index).expect("test case works");
+			assert_eq!((pallet, call.name()), ("Balances", "transfer_allow_death"));
+			assert!(metadata.validate_call::<TransferAllowDeath>().is_ok());
+		}
+	}
+
+	#[test]
+	fn payloads_of_the_runtimes_decode_with_live_metadata() {
+		for (name, chain, spec_version, tx_version) in FIXTURE_CHAINS.iter() {
+			let bytes = fixture(name);
+			let metadata = Metadata::from_bytes(&bytes).expect("test case works");
+			let extensions = match RuntimeMetadataPrefixed::decode(&mut &bytes[..]) {
+				Ok(RuntimeMetadataPrefixed(_, RuntimeMetadata::V15(meta))) => {
+					meta.extrinsic.signed_extensions
+				}
+				_ => panic!("Fixtures are V15 metadata"),
+			};
+
+			let call_index =
+				metadata.call_index("Balances", "transfer_allow_death").expect("test case works");
+			let args = [&[0u8][..], &[2; 32], &[0x30]].concat();
+			let payload = with_runtime!(*chain, R => {
+				let tx: Tx<RawArgs, R> = Tx::new(TxConfig {
+					call: GenericCall::new(call_index, RawArgs(args)),
+					address: sp_core::crypto::AccountId32::from([1; 32]).into(),
+					nonce: 7,
+					tx_version: *tx_version,
+					spec_version: *spec_version,
+					genesis_hash: chain.genesis_hash(),
+					mortality: Mortality::Mortal(MortalConfig {
+						period: 64,
+						checkpoint_block_number: 100,
+						checkpoint_block_hash: H256::repeat_byte(3),
+					}),
+					tip: 1_000,
+					fee_asset: None,
+					metadata_hash: Some([4; 32]),
+				})
+				.expect("test case works");
+				tx.signed_payload().expect("test case works").encode()
+			});
+
+			// The call, then the extra and the additional signed data of each signed extension,
+			// decoded with the types of the runtime, each followed by those of the enabled
+			// `CheckMetadataHash` the runtime does not have yet
+			let types = metadata.types().expect("test case works");
+			let input = &mut &payload[..];
+			metadata.decode_call(input).expect("test case works");
+			for extension in extensions.iter() {
+				decode_value(types, extension.ty.id, 0, input).expect("test case works");
+			}
+			assert_eq!(u8::decode(input), Ok(1));
+			let additional_signed = extensions
+				.iter()
+				.map(|extension| decode_value(types, extension.additional_signed.id, 0, input))
+				.collect::<Result<Vec<_>, _>>()
+				.expect("test case works");
+			assert_eq!(<Option<[u8; 32]>>::decode(input), Ok(Some([4; 32])));
+			assert!(input.is_empty());
+			assert_eq!(additional_signed[1], Value::UInt((*spec_version).into()));
+			assert_eq!(additional_signed[2], Value::UInt((*tx_version).into()));
+		}
+	}
+}
@@ -0,0 +1,264 @@
+use frame_metadata::{
+	v14::RuntimeMetadataV14,
+	v15::{self, CustomMetadata, OuterEnums, RuntimeMetadataV15},
+	RuntimeMetadata, RuntimeMetadataPrefixed, META_RESERVED,
+};
+use scale::Decode;
+
+pub use merkleized_metadata::ExtraInfo;
+
+/// Compute the RFC-78 metadata hash, the hash the `CheckMetadataHash` signed extension adds to the
+/// additional signed data, from the bytes of SCALE encoded V14 or V15 `RuntimeMetadataPrefixed`.
+///
+/// `extra_info` is the information about the runtime and its native token that is part of the
+/// hash besides the metadata, which has to match the runtime's for the hash to be accepted.
+pub fn metadata_hash(
+	bytes: &[u8],
+	extra_info: ExtraInfo,
+) -> Result<[u8; 32], substrate_airgapped::Error> {
+	let metadata = RuntimeMetadataPrefixed::decode(&mut &bytes[..])
+		.map_err(|_| "Failed to decode runtime metadata")?;
+	if metadata.0 != META_RESERVED {
+		return Err("Failed to decode runtime metadata".into());
+	}
+	let metadata = match metadata.1 {
+		RuntimeMetadata::V14(meta) => RuntimeMetadata::V15(v14_to_v15(meta)?),
+		RuntimeMetadata::V15(meta) => RuntimeMetadata::V15(meta),
+		_ => return Err("Metadata hash requires V14 or V15 metadata".into()),
+	};
+
+	let digest = merkleized_metadata::generate_metadata_digest(&metadata, extra_info)
+		.map_err(|_| "Failed to compute the metadata hash")?;
+
+	Ok(digest.hash())
+}
+
+/// Convert V14 metadata to the V15 metadata the metadata hash is defined on. Only the types the
+/// extrinsic metadata refers to are part of the hash, so it matches the one of the V15 metadata of
+/// the runtime. The runtime APIs and the docs of the pallets are not in V14 metadata.
+fn v14_to_v15(meta: RuntimeMetadataV14) -> Result<RuntimeMetadataV15, substrate_airgapped::Error> {
+	let extrinsic_ty = meta
+		.types
+		.resolve(meta.extrinsic.ty.id)
+		.ok_or("Extrinsic type could not be found in runtime metadata")?;
+	// The address, call, signature and extra types are the parameters of `UncheckedExtrinsic`
+	let type_param = |name: &str| {
+		extrinsic_ty
+			.type_params
+			.iter()
+			.find(|param| param.name == name)
+			.and_then(|param| param.ty)
+			.ok_or("Extrinsic type is missing a type parameter")
+	};
+	let extrinsic = v15::ExtrinsicMetadata {
+		version: meta.extrinsic.version,
+		address_ty: type_param("Address")?,
+		call_ty: type_param("Call")?,
+		signature_ty: type_param("Signature")?,
+		extra_ty: type_param("Extra")?,
+		signed_extensions: meta
+			.extrinsic
+			.signed_extensions
+			.into_iter()
+			.map(|extension| v15::SignedExtensionMetadata {
+				identifier: extension.identifier,
+				ty: extension.ty,
+				additional_signed: extension.additional_signed,
+			})
+			.collect(),
+	};
+	let pallets = meta
+		.pallets
+		.into_iter()
+		.map(|pallet| v15::PalletMetadata {
+			name: pallet.name,
+			storage: pallet.storage,
+			calls: pallet.calls,
+			event: pallet.event,
+			constants: pallet.constants,
+			error: pallet.error,
+			index: pallet.index,
+			docs: vec![],
+		})
+		.collect();
+	// The outer enums are not part of the hash, and the error enum is not in the registry of
+	// runtimes older than V15 metadata
+	let (types, runtime_ty) = (&meta.types, meta.ty);
+	let outer_enum_ty = |name: &str| {
+		types
+			.types
+			.iter()
+			.find(|ty| ty.ty.path.segments.last().map(String::as_str) == Some(name))
+			.map_or(runtime_ty, |ty| ty.id.into())
+	};
+	let outer_enums = OuterEnums {
+		call_enum_ty: extrinsic.call_ty,
+		event_enum_ty: outer_enum_ty("RuntimeEvent"),
+		error_enum_ty: outer_enum_ty("RuntimeError"),
+	};
+
+	Ok(RuntimeMetadataV15 {
+		types: meta.types,
+		pallets,
+		extrinsic,
+		ty: meta.ty,
+		apis: vec![],
+		outer_enums,
+		custom: CustomMetadata { map: Default::default() },
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::fixture;
+	use core::marker::PhantomData;
+	use frame_metadata::v14::{self, ExtrinsicMetadata, SignedExtensionMetadata};
+	use scale::Encode;
+	use scale_info::{meta_type, TypeInfo};
+
+	#[allow(dead_code, non_camel_case_types)]
+	#[derive(TypeInfo)]
+	enum Call {
+		remark { remark: Vec<u8> },
+	}
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo)]
+	struct UncheckedExtrinsic<Address, Call, Signature, Extra>(
+		PhantomData<(Address, Call, Signature, Extra)>,
+	);
+
+	fn v14_metadata() -> RuntimeMetadataV14 {
+		let extrinsic = ExtrinsicMetadata {
+			ty: meta_type::<UncheckedExtrinsic<[u8; 32], Call, [u8; 64], (u8, u32)>>(),
+			version: 4,
+			signed_extensions: vec![SignedExtensionMetadata {
+				identifier: "CheckMetadataHash",
+				ty: meta_type::<u8>(),
+				additional_signed: meta_type::<Option<[u8; 32]>>(),
+			}],
+		};
+
+		RuntimeMetadataV14::new(vec![], extrinsic, meta_type::<()>())
+	}
+
+	fn extra_info(spec_version: u32) -> ExtraInfo {
+		ExtraInfo {
+			spec_version,
+			spec_name: "kusama".into(),
+			base58_prefix: 2,
+			decimals: 12,
+			token_symbol: "KSM".into(),
+		}
+	}
+
+	#[test]
+	fn metadata_hash_works() {
+		let v14 = RuntimeMetadataPrefixed::from(v14_metadata()).encode();
+		let hash = metadata_hash(&v14, extra_info(9430)).expect("test case works");
+
+		let v15 =
+			RuntimeMetadataPrefixed::from(v14_to_v15(v14_metadata()).expect("test case works"));
+		assert_eq!(metadata_hash(&v15.encode(), extra_info(9430)), Ok(hash));
+		assert_ne!(metadata_hash(&v14, extra_info(9431)), Ok(hash));
+	}
+
+	#[test]
+	fn metadata_hash_requires_v14_or_v15() {
+		let v13 = RuntimeMetadataPrefixed(
+			META_RESERVED,
+			RuntimeMetadata::V13(frame_metadata::v13::RuntimeMetadataV13 {
+				modules: frame_metadata::decode_different::DecodeDifferent::Decoded(vec![]),
+				extrinsic: frame_metadata::v13::ExtrinsicMetadata {
+					version: 4,
+					signed_extensions: vec![],
+				},
+			}),
+		);
+		assert!(metadata_hash(&v13.encode(), extra_info(9430)).is_err());
+		assert!(metadata_hash(&[0, 1, 2], extra_info(9430)).is_err());
+
+		let mut extrinsic_without_params = v14_metadata();
+		extrinsic_without_params.extrinsic.ty = extrinsic_without_params.ty;
+		let v14 = RuntimeMetadataPrefixed::from(extrinsic_without_params).encode();
+		assert!(metadata_hash(&v14, extra_info(9430)).is_err());
+	}
+
+	/// V14 metadata of the runtime of a V15 fixture, with the registry of the V15 metadata.
+	fn v14_of_fixture(name: &str) -> RuntimeMetadataV14 {
+		let meta = match RuntimeMetadataPrefixed::decode(&mut &fixture(name)[..]) {
+			Ok(RuntimeMetadataPrefixed(_, RuntimeMetadata::V15(meta))) => meta,
+			_ => panic!("Fixtures are V15 metadata"),
+		};
+		let extrinsic_ty = meta
+			.types
+			.types
+			.iter()
+			.find(|ty| ty.ty.path.segments.last().map(String::as_str) == Some("UncheckedExtrinsic"))
+			.expect("test case works")
+			.id;
+
+		RuntimeMetadataV14 {
+			pallets: meta
+				.pallets
+				.into_iter()
+				.map(|pallet| v14::PalletMetadata {
+					name: pallet.name,
+					storage: pallet.storage,
+					calls: pallet.calls,
+					event: pallet.event,
+					constants: pallet.constants,
+					error: pallet.error,
+					index: pallet.index,
+				})
+				.collect(),
+			extrinsic: ExtrinsicMetadata {
+				ty: extrinsic_ty.into(),
+				version: meta.extrinsic.version,
+				signed_extensions: meta
+					.extrinsic
+					.signed_extensions
+					.into_iter()
+					.map(|extension| SignedExtensionMetadata {
+						identifier: extension.identifier,
+						ty: extension.ty,
+						additional_signed: extension.additional_signed,
+					})
+					.collect(),
+			},
+			types: meta.types,
+			ty: meta.ty,
+		}
+	}
+
+	#[test]
+	fn metadata_hash_of_live_runtimes_works() {
+		// Digests the `merkleized-metadata` crate publishes for the fixtures, with its `ExtraInfo`
+		let extra_info = ExtraInfo {
+			spec_version: 1,
+			spec_name: "nice".into(),
+			base58_prefix: 1,
+			decimals: 1,
+			token_symbol: "lol".into(),
+		};
+		let digests = [
+			("polkadot", "72b3e70cb722edeb45a9380720ecad79b09b4113ab2dee5f5d974f170fb77a7e"),
+			("kusama", "23d7a31034edf29f4a5977ffc3075aba8087c422026e9bf4aaea8bc8192d6a23"),
+			("rococo", "6619a31025a9a14086a34da4ede7ed61258b9f55c12baae8bc801317869d2dfb"),
+		];
+
+		for (name, digest) in digests.iter() {
+			let hash = metadata_hash(&fixture(name), extra_info.clone()).expect("test case works");
+			assert_eq!(hex::encode(hash), *digest);
+
+			let v14 = v14_of_fixture(name);
+			let v15 = v14_to_v15(v14.clone()).expect("test case works");
+			let v15_pallets: Vec<_> = v15.pallets.iter().map(|p| (&p.name, p.index)).collect();
+			let v14_pallets: Vec<_> = v14.pallets.iter().map(|p| (&p.name, p.index)).collect();
+			assert_eq!(v15_pallets, v14_pallets);
+			let v14 = RuntimeMetadataPrefixed::from(v14).encode();
+			assert_eq!(metadata_hash(&v14, extra_info.clone()), Ok(hash));
+		}
+	}
+}
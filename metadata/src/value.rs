@@ -0,0 +1,153 @@
+use core::fmt;
+use sp_core::crypto::{AccountId32, Ss58AddressFormat, Ss58Codec};
+
+/// Generic representation of a value of a type in the runtime's type registry, used to construct
+/// and decode calls whose argument types are not known at compile time.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+	/// A `bool`.
+	Bool(bool),
+	/// A `char`.
+	Char(char),
+	/// An unsigned integer, of any width up to `u128`.
+	UInt(u128),
+	/// A signed integer, of any width up to `i128`.
+	Int(i128),
+	/// A `String`. Also accepted for bytes (as hex with a leading `0x`), 32 byte accounts (as
+	/// SS58), integers (as decimal) and enum variants without fields (as the variant name).
+	Str(String),
+	/// Bytes, for a sequence or array of `u8`.
+	Bytes(Vec<u8>),
+	/// A sequence, array or tuple of values.
+	Sequence(Vec<Value>),
+	/// A struct or tuple struct.
+	Composite(Fields),
+	/// An enum variant, by name.
+	Variant(String, Fields),
+	/// A compact encoded value. When encoding, values of compact types can also be given
+	/// without this wrapper.
+	Compact(Box<Value>),
+	/// A 32 byte account, as decoded from an `AccountId32`. Shown as an SS58 address.
+	Account(AccountId32),
+}
+
+/// Fields of a `Value::Composite` or `Value::Variant`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fields {
+	/// Fields by name, in any order.
+	Named(Vec<(String, Value)>),
+	/// Fields by position.
+	Unnamed(Vec<Value>),
+}
+
+impl Fields {
+	/// Number of fields.
+	pub fn len(&self) -> usize {
+		match self {
+			Fields::Named(fields) => fields.len(),
+			Fields::Unnamed(fields) => fields.len(),
+		}
+	}
+
+	/// Whether there are no fields.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl Value {
+	/// Display the value with accounts as SS58 addresses with the network prefix `format`,
+	/// instead of the default network prefix of `sp_core`.
+	pub fn display_with_ss58_format(&self, format: Ss58AddressFormat) -> impl fmt::Display + '_ {
+		WithSs58Format { value: self, format: Some(format) }
+	}
+
+	fn fmt_with(&self, f: &mut fmt::Formatter, format: Option<Ss58AddressFormat>) -> fmt::Result {
+		let display = |value| WithSs58Format { value, format };
+		match self {
+			Value::Bool(b) => write!(f, "{}", b),
+			Value::Char(c) => write!(f, "{:?}", c),
+			Value::UInt(n) => write!(f, "{}", n),
+			Value::Int(n) => write!(f, "{}", n),
+			Value::Str(s) => write!(f, "{:?}", s),
+			Value::Bytes(bytes) => write!(f, "0x{}", hex::encode(bytes)),
+			Value::Sequence(values) => {
+				write!(f, "[")?;
+				for (i, value) in values.iter().enumerate() {
+					if i > 0 {
+						write!(f, ", ")?;
+					}
+					write!(f, "{}", display(value))?;
+				}
+				write!(f, "]")
+			}
+			Value::Composite(fields) => fields.fmt_with(f, format),
+			Value::Variant(name, fields) if fields.is_empty() => write!(f, "{}", name),
+			Value::Variant(name, fields @ Fields::Named(_)) => {
+				write!(f, "{} ", name)?;
+				fields.fmt_with(f, format)
+			}
+			Value::Variant(name, fields) => {
+				write!(f, "{}", name)?;
+				fields.fmt_with(f, format)
+			}
+			Value::Compact(value) => value.fmt_with(f, format),
+			Value::Account(account) => match format {
+				Some(format) => write!(f, "{}", account.to_ss58check_with_version(format)),
+				None => write!(f, "{}", account.to_ss58check()),
+			},
+		}
+	}
+}
+
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.fmt_with(f, None)
+	}
+}
+
+impl Fields {
+	fn fmt_with(&self, f: &mut fmt::Formatter, format: Option<Ss58AddressFormat>) -> fmt::Result {
+		let display = |value| WithSs58Format { value, format };
+		match self {
+			Fields::Named(fields) => {
+				write!(f, "{{ ")?;
+				for (i, (name, value)) in fields.iter().enumerate() {
+					if i > 0 {
+						write!(f, ", ")?;
+					}
+					write!(f, "{}: {}", name, display(value))?;
+				}
+				write!(f, " }}")
+			}
+			Fields::Unnamed(values) => {
+				write!(f, "(")?;
+				for (i, value) in values.iter().enumerate() {
+					if i > 0 {
+						write!(f, ", ")?;
+					}
+					write!(f, "{}", display(value))?;
+				}
+				write!(f, ")")
+			}
+		}
+	}
+}
+
+impl fmt::Display for Fields {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.fmt_with(f, None)
+	}
+}
+
+/// Displays a `Value` with accounts as SS58 addresses with the given network prefix.
+struct WithSs58Format<'a> {
+	value: &'a Value,
+	format: Option<Ss58AddressFormat>,
+}
+
+impl fmt::Display for WithSs58Format<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.value.fmt_with(f, self.format)
+	}
+}
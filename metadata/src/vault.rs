@@ -0,0 +1,112 @@
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed, META_RESERVED};
+use scale::Decode;
+use sp_core::H256;
+use substrate_airgapped::vault::{load_metadata_payload, Verifier};
+
+/// Create the Polkadot Vault payload to load metadata on the device, from the bytes of SCALE
+/// encoded V14 or V15 `RuntimeMetadataPrefixed` of the runtime with the given spec version,
+/// optionally signed by a verifier.
+///
+/// Errors if the spec version of the `Version` constant of the `System` pallet in the metadata,
+/// which the device reads to identify the metadata, is not `spec_version`.
+pub fn vault_load_metadata(
+	bytes: &[u8],
+	spec_version: u32,
+	genesis_hash: H256,
+	verifier: Option<Verifier>,
+) -> Result<Vec<u8>, substrate_airgapped::Error> {
+	if runtime_spec_version(bytes)? != spec_version {
+		return Err("Spec version of the metadata is not the given spec version".into());
+	}
+
+	Ok(load_metadata_payload(bytes, genesis_hash, verifier))
+}
+
+/// Read the spec version from the `Version` constant of the `System` pallet.
+fn runtime_spec_version(bytes: &[u8]) -> Result<u32, substrate_airgapped::Error> {
+	let metadata = RuntimeMetadataPrefixed::decode(&mut &bytes[..])
+		.map_err(|_| "Failed to decode runtime metadata")?;
+	if metadata.0 != META_RESERVED {
+		return Err("Failed to decode runtime metadata".into());
+	}
+	let version = match metadata.1 {
+		RuntimeMetadata::V14(meta) => meta
+			.pallets
+			.into_iter()
+			.find(|pallet| pallet.name == "System")
+			.and_then(|pallet| pallet.constants.into_iter().find(|c| c.name == "Version"))
+			.map(|constant| constant.value),
+		RuntimeMetadata::V15(meta) => meta
+			.pallets
+			.into_iter()
+			.find(|pallet| pallet.name == "System")
+			.and_then(|pallet| pallet.constants.into_iter().find(|c| c.name == "Version"))
+			.map(|constant| constant.value),
+		_ => return Err("Loading metadata on Polkadot Vault requires V14 or V15 metadata".into()),
+	}
+	.ok_or("Version constant of the System pallet could not be found in runtime metadata")?;
+
+	// `RuntimeVersion` starts with the spec name, the implementation name and the authoring version
+	let (_, _, _, spec_version) = <(String, String, u32, u32)>::decode(&mut &version[..])
+		.map_err(|_| "Failed to decode the runtime version")?;
+
+	Ok(spec_version)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_metadata::v14::{
+		ExtrinsicMetadata, PalletConstantMetadata, PalletMetadata, RuntimeMetadataV14,
+	};
+	use scale::Encode;
+	use scale_info::meta_type;
+	use sp_core::{sr25519, Pair};
+
+	fn metadata(version: Vec<u8>) -> Vec<u8> {
+		let system = PalletMetadata {
+			name: "System",
+			storage: None,
+			calls: None,
+			event: None,
+			constants: vec![PalletConstantMetadata {
+				name: "Version",
+				ty: meta_type::<(String, String, u32, u32)>(),
+				value: version,
+				docs: vec![],
+			}],
+			error: None,
+			index: 0,
+		};
+		let extrinsic =
+			ExtrinsicMetadata { ty: meta_type::<()>(), version: 4, signed_extensions: vec![] };
+
+		RuntimeMetadataPrefixed::from(RuntimeMetadataV14::new(
+			vec![system],
+			extrinsic,
+			meta_type::<()>(),
+		))
+		.encode()
+	}
+
+	#[test]
+	fn vault_load_metadata_works() {
+		let bytes = metadata(("kusama", "parity-kusama", 2u32, 9430u32).encode());
+		let genesis_hash = H256::repeat_byte(1);
+
+		assert_eq!(
+			vault_load_metadata(&bytes, 9430, genesis_hash, None),
+			Ok(load_metadata_payload(&bytes, genesis_hash, None))
+		);
+		let pair = sr25519::Pair::from_string("//Alice", None).expect("test case works");
+		let payload =
+			vault_load_metadata(&bytes, 9430, genesis_hash, Some(Verifier::Sr25519(&pair)))
+				.expect("test case works");
+		assert_eq!(payload[..3], [0x53, 0x01, 0x80]);
+
+		assert!(vault_load_metadata(&bytes, 9431, genesis_hash, None).is_err());
+		let invalid_version = metadata(vec![1, 2, 3]);
+		assert!(vault_load_metadata(&invalid_version, 9430, genesis_hash, None).is_err());
+		assert!(vault_load_metadata(&[0, 1, 2], 9430, genesis_hash, None).is_err());
+	}
+}
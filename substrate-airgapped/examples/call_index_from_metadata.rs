@@ -1,10 +1,7 @@
-use codec::Decode;
-use core::convert::TryInto;
-use substrate_airgapped::{balances::Transfer, GenericCall, KusamaRuntime};
+use substrate_airgapped::{address_from_ss58, balances::Transfer, GenericCall, KusamaRuntime};
 
-use metadata::{Metadata, RuntimeMetadataPrefixed};
+use metadata::Metadata;
 use serde::{Deserialize, Serialize};
-use sp_keyring::AccountKeyring;
 use sp_runtime::DeserializeOwned;
 
 /// To get a local development node started, follow the instructions in the
@@ -24,8 +21,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 			Ok(hex::decode(no_prefix)?)
 		})?;
 
-	let metadata_prefixed = RuntimeMetadataPrefixed::decode(&mut &metadata_bytes[..])?;
-	let metadata: Metadata = metadata_prefixed.try_into().expect("example to work");
+	let metadata = Metadata::from_bytes(&metadata_bytes).expect("example to work");
 
 	type TransferType = Transfer<KusamaRuntime>;
 
@@ -33,8 +29,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 	let call_index = metadata.find_call_index::<TransferType>().expect("example to work");
 	println!("Call index for balances::Transfer: {:#?}", call_index);
 
-	let args: Transfer<KusamaRuntime> =
-		Transfer { to: AccountKeyring::Bob.to_account_id().into(), amount: 123_456_789 };
+	// Bob's address, with the Kusama network prefix
+	let bob =
+		address_from_ss58::<KusamaRuntime>("FoQJpPyadYccjavVdTWxpxU7rUEaYhfLCPwXgkfD6Zat9QP")?;
+	let args: Transfer<KusamaRuntime> = Transfer { to: bob, amount: 123_456_789 };
 	// You can then use the returned call index to construct a `GenericCall` - the type needed to
 	// construct a `UncheckedExtrinsic`
 	let transfer_call = GenericCall::new(call_index, args);
@@ -2,19 +2,20 @@ use codec::Encode;
 use primitive_types::H256;
 use sp_runtime::{generic::Header, traits::BlakeTwo256, DeserializeOwned};
 use substrate_airgapped::{
-	balances::Transfer, CallIndex, GenericCall, KusamaRuntime, MortalConfig, Mortality, Tx,
-	TxConfig,
+	address_from_ss58, balances::Transfer, CallIndex, GenericCall, KusamaRuntime, MortalConfig,
+	Mortality, Tx, TxConfig,
 };
 
 // Example deps
-use hex;
-use reqwest;
 use serde::{Deserialize, Serialize};
 use sp_keyring::AccountKeyring;
 use sp_version::RuntimeVersion;
 use std::str::FromStr;
 
 type KusamaTransfer = Transfer<KusamaRuntime>;
+
+const ALICE: &str = "HNZata7iMYWmk5RvZRTiAsSDhV8366zq2YGb3tLH5Upf74F";
+const BOB: &str = "FoQJpPyadYccjavVdTWxpxU7rUEaYhfLCPwXgkfD6Zat9QP";
 type Error = Box<dyn std::error::Error>;
 
 fn main() -> Result<(), Error> {
@@ -23,13 +24,14 @@ fn main() -> Result<(), Error> {
 	let runtime_version: RuntimeVersion =
 		rpc_to_local_node("chain_getRuntimeVersion", vec![block_hash])?.result;
 	let header: Header<u32, BlakeTwo256> =
-		rpc_to_local_node("chain_getHeader", vec![block_hash.clone()])?.result;
+		rpc_to_local_node("chain_getHeader", vec![block_hash])?.result;
 
 	let genesis_hash: H256 = rpc_to_local_node("chain_getBlockHash", vec![0])
 		.map(|rpc_res: RpcRes<String>| H256::from_str(&rpc_res.result[2..]))??;
 
-	let alice_addr = AccountKeyring::Alice.to_account_id().into();
-	let bob_addr = AccountKeyring::Bob.to_account_id().into();
+	// Addresses are given as SS58 with the Kusama network prefix
+	let alice_addr = address_from_ss58::<KusamaRuntime>(ALICE)?;
+	let bob_addr = address_from_ss58::<KusamaRuntime>(BOB)?;
 	let call =
 		GenericCall::new(CallIndex::new(5, 0), KusamaTransfer { to: bob_addr, amount: 123_456 });
 
@@ -46,7 +48,9 @@ fn main() -> Result<(), Error> {
 			checkpoint_block_hash: block_hash,
 		}),
 		tip: 100,
-	});
+		fee_asset: None,
+		metadata_hash: None,
+	})?;
 
 	let signed_tx = tx.signed_tx_from_pair(AccountKeyring::Alice.pair()).expect("example to work");
 	println!("Tx (UncheckedExtrinsic): {:#?}\n", signed_tx);
@@ -79,8 +83,8 @@ fn rpc_to_local_node<T: Serialize, U: DeserializeOwned>(
 	method: &str,
 	params: Vec<T>,
 ) -> Result<RpcRes<U>, Error> {
-	const LOCAL_NODE_URL: &'static str = "http://localhost:9933";
-	const TWO_ZERO: &'static str = "2.0";
+	const LOCAL_NODE_URL: &str = "http://localhost:9933";
+	const TWO_ZERO: &str = "2.0";
 	let client = reqwest::blocking::Client::new();
 
 	let req_body = RpcReq { jsonrpc: TWO_ZERO, id: 1, method, params };
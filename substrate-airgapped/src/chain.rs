@@ -0,0 +1,296 @@
+use crate::{
+	runtimes::{
+		KusamaRuntime, PolkadotRuntime, RococoRuntime, Runtime, StatemineRuntime, StatemintRuntime,
+		WestendRuntime,
+	},
+	tx::extra::Extra,
+	Error,
+};
+use core::{fmt, str::FromStr};
+use sp_core::{
+	crypto::{Ss58AddressFormat, Ss58Codec},
+	H256,
+};
+use sp_runtime::traits::SignedExtension;
+
+/// Registry of the networks this crate has a `Runtime` for, used to check a transaction is for the
+/// network it is expected to be for, or to detect the network from a genesis hash.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Chain {
+	/// Polkadot, using `PolkadotRuntime`.
+	Polkadot,
+	/// Kusama, using `KusamaRuntime`.
+	Kusama,
+	/// Westend, using `WestendRuntime`.
+	Westend,
+	/// Rococo, using `RococoRuntime`.
+	Rococo,
+	/// Statemint, the asset hub of Polkadot, using `StatemintRuntime`.
+	Statemint,
+	/// Statemine, the asset hub of Kusama, using `StatemineRuntime`.
+	Statemine,
+}
+
+impl Chain {
+	/// All known chains.
+	pub const ALL: [Chain; 6] = [
+		Chain::Polkadot,
+		Chain::Kusama,
+		Chain::Westend,
+		Chain::Rococo,
+		Chain::Statemint,
+		Chain::Statemine,
+	];
+
+	/// The chain with the given genesis hash, if it is known.
+	pub fn from_genesis_hash(genesis_hash: &[u8]) -> Option<Chain> {
+		Chain::ALL.iter().copied().find(|chain| chain.genesis_hash().as_bytes() == genesis_hash)
+	}
+
+	/// Name of the chain.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Chain::Polkadot => "Polkadot",
+			Chain::Kusama => "Kusama",
+			Chain::Westend => "Westend",
+			Chain::Rococo => "Rococo",
+			Chain::Statemint => "Statemint",
+			Chain::Statemine => "Statemine",
+		}
+	}
+
+	/// Hash of the chain's genesis block.
+	pub fn genesis_hash(&self) -> H256 {
+		match self {
+			Chain::Polkadot => PolkadotRuntime::GENESIS_HASH,
+			Chain::Kusama => KusamaRuntime::GENESIS_HASH,
+			Chain::Westend => WestendRuntime::GENESIS_HASH,
+			Chain::Rococo => RococoRuntime::GENESIS_HASH,
+			Chain::Statemint => StatemintRuntime::GENESIS_HASH,
+			Chain::Statemine => StatemineRuntime::GENESIS_HASH,
+		}
+	}
+
+	/// Network prefix of the chain's SS58 addresses.
+	pub fn ss58_format(&self) -> Ss58AddressFormat {
+		match self {
+			Chain::Polkadot | Chain::Statemint => Ss58AddressFormat::PolkadotAccount,
+			Chain::Kusama | Chain::Statemine => Ss58AddressFormat::KusamaAccount,
+			Chain::Westend | Chain::Rococo => Ss58AddressFormat::SubstrateAccount,
+		}
+	}
+
+	/// Symbol of the chain's native token.
+	pub fn token_symbol(&self) -> &'static str {
+		match self {
+			Chain::Polkadot | Chain::Statemint => "DOT",
+			Chain::Kusama | Chain::Statemine => "KSM",
+			Chain::Westend => "WND",
+			Chain::Rococo => "ROC",
+		}
+	}
+
+	/// Number of decimals of the chain's native token, i.e. a token is `10^decimals` of the
+	/// runtime's `Balance`.
+	pub fn token_decimals(&self) -> u8 {
+		match self {
+			Chain::Polkadot | Chain::Statemint => 10,
+			Chain::Kusama | Chain::Westend | Chain::Rococo | Chain::Statemine => 12,
+		}
+	}
+
+	/// Identifiers of the signed extensions of the chain's runtime, in the order their data is
+	/// encoded in a transaction.
+	pub fn signed_extensions(&self) -> Vec<&'static str> {
+		crate::with_runtime!(self, R => Extra::<R>::identifier())
+	}
+
+	/// Parse an SS58 address of this chain. Errors if the address has the network prefix of
+	/// another chain.
+	pub fn parse_account<A: Ss58Codec>(&self, address: &str) -> Result<A, Error> {
+		let (account, format) = A::from_ss58check_with_version(address)?;
+		if format != self.ss58_format() {
+			return Err("SS58 address prefix does not match the chain".into());
+		}
+
+		Ok(account)
+	}
+
+	/// Format an account as an SS58 address of this chain.
+	pub fn format_account<A: Ss58Codec>(&self, account: &A) -> String {
+		account.to_ss58check_with_version(self.ss58_format())
+	}
+
+	/// Check `genesis_hash` is the genesis hash of this chain.
+	pub fn check_genesis_hash(&self, genesis_hash: &[u8]) -> Result<(), Error> {
+		if self.genesis_hash().as_bytes() == genesis_hash {
+			Ok(())
+		} else {
+			Err("Genesis hash does not match the chain of the runtime".into())
+		}
+	}
+}
+
+/// Check `genesis_hash` is the genesis hash of the chain of runtime `R`. Always passes for
+/// runtimes that are not tied to a known chain.
+pub fn check_genesis_hash<R: Runtime>(genesis_hash: &R::Hash) -> Result<(), Error> {
+	match R::CHAIN {
+		Some(chain) => chain.check_genesis_hash(genesis_hash.as_ref()),
+		None => Ok(()),
+	}
+}
+
+/// Parse an SS58 address into an account of runtime `R`. Errors if the runtime is for a known
+/// `Chain` and the address has the network prefix of another chain.
+pub fn account_from_ss58<R>(address: &str) -> Result<R::AccountId, Error>
+where
+	R: Runtime,
+	R::AccountId: Ss58Codec,
+{
+	match R::CHAIN {
+		Some(chain) => chain.parse_account(address),
+		None => Ok(R::AccountId::from_ss58check_with_version(address)?.0),
+	}
+}
+
+/// Parse an SS58 address into an address of runtime `R`, see `account_from_ss58`.
+pub fn address_from_ss58<R>(address: &str) -> Result<R::Address, Error>
+where
+	R: Runtime,
+	R::AccountId: Ss58Codec,
+{
+	account_from_ss58::<R>(address).map(Into::into)
+}
+
+/// Format an account of runtime `R` as an SS58 address, with the network prefix of the runtime's
+/// chain or the generic Substrate prefix if it has none.
+pub fn account_to_ss58<R>(account: &R::AccountId) -> String
+where
+	R: Runtime,
+	R::AccountId: Ss58Codec,
+{
+	let format = R::CHAIN.map_or(Ss58AddressFormat::SubstrateAccount, |chain| chain.ss58_format());
+
+	account.to_ss58check_with_version(format)
+}
+
+impl fmt::Display for Chain {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl FromStr for Chain {
+	type Err = Error;
+
+	/// Parse the name of a chain, ignoring case.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Chain::ALL
+			.iter()
+			.copied()
+			.find(|chain| chain.name().eq_ignore_ascii_case(s))
+			.ok_or_else(|| "Unknown chain".into())
+	}
+}
+
+/// Evaluate an expression with a type alias for the `Runtime` of a `Chain`.
+///
+/// ```
+/// use substrate_airgapped::{with_runtime, Chain};
+///
+/// let chain = Chain::Kusama;
+/// let genesis_hash = with_runtime!(chain, R => R::GENESIS_HASH);
+/// assert_eq!(genesis_hash, chain.genesis_hash());
+/// ```
+#[macro_export]
+macro_rules! with_runtime {
+	($chain:expr, $runtime:ident => $body:expr) => {
+		match $chain {
+			$crate::Chain::Polkadot => {
+				#[allow(dead_code)]
+				type $runtime = $crate::PolkadotRuntime;
+				$body
+			}
+			$crate::Chain::Kusama => {
+				#[allow(dead_code)]
+				type $runtime = $crate::KusamaRuntime;
+				$body
+			}
+			$crate::Chain::Westend => {
+				#[allow(dead_code)]
+				type $runtime = $crate::WestendRuntime;
+				$body
+			}
+			$crate::Chain::Rococo => {
+				#[allow(dead_code)]
+				type $runtime = $crate::RococoRuntime;
+				$body
+			}
+			$crate::Chain::Statemint => {
+				#[allow(dead_code)]
+				type $runtime = $crate::StatemintRuntime;
+				$body
+			}
+			$crate::Chain::Statemine => {
+				#[allow(dead_code)]
+				type $runtime = $crate::StatemineRuntime;
+				$body
+			}
+		}
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tx::extra::Extra;
+	use sp_core::crypto::AccountId32;
+	use sp_keyring::AccountKeyring;
+	use sp_runtime::traits::SignedExtension;
+
+	#[test]
+	fn chain_from_genesis_hash_works() {
+		for chain in Chain::ALL.iter() {
+			assert_eq!(Chain::from_genesis_hash(chain.genesis_hash().as_bytes()), Some(*chain));
+			assert_eq!(chain.name().to_lowercase().parse::<Chain>(), Ok(*chain));
+		}
+		assert_eq!(Chain::from_genesis_hash(&[0; 32]), None);
+		assert!("Kulupu".parse::<Chain>().is_err());
+	}
+
+	#[test]
+	fn chain_matches_its_runtime() {
+		for chain in Chain::ALL.iter() {
+			let (runtime_chain, extensions) =
+				with_runtime!(chain, R => (R::CHAIN, Extra::<R>::identifier()));
+			assert_eq!(runtime_chain, Some(*chain));
+			assert_eq!(chain.signed_extensions(), extensions);
+		}
+	}
+
+	#[test]
+	fn ss58_addresses_are_checked_against_the_chain() {
+		let alice = AccountKeyring::Alice.to_account_id();
+		let polkadot = "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5";
+		let kusama = "HNZata7iMYWmk5RvZRTiAsSDhV8366zq2YGb3tLH5Upf74F";
+
+		assert_eq!(Chain::Polkadot.format_account(&alice), polkadot);
+		assert_eq!(account_to_ss58::<KusamaRuntime>(&alice), kusama);
+		assert_eq!(account_from_ss58::<PolkadotRuntime>(polkadot), Ok(alice.clone()));
+		assert_eq!(address_from_ss58::<KusamaRuntime>(kusama), Ok(alice.into()));
+
+		assert!(account_from_ss58::<PolkadotRuntime>(kusama).is_err());
+		assert!(Chain::Westend.parse_account::<AccountId32>(polkadot).is_err());
+		assert!(account_from_ss58::<KusamaRuntime>(
+			"HNZata7iMYWmk5RvZRTiAsSDhV8366zq2YGb3tLH5Upf74G"
+		)
+		.is_err());
+	}
+
+	#[test]
+	fn check_genesis_hash_rejects_other_chains() {
+		assert!(check_genesis_hash::<KusamaRuntime>(&KusamaRuntime::GENESIS_HASH).is_ok());
+		assert!(check_genesis_hash::<KusamaRuntime>(&PolkadotRuntime::GENESIS_HASH).is_err());
+		assert!(check_genesis_hash::<PolkadotRuntime>(&H256::zero()).is_err());
+	}
+}
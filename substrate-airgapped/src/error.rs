@@ -1,4 +1,5 @@
 use core::{fmt, num::TryFromIntError};
+use sp_core::crypto::PublicError;
 use sp_runtime::transaction_validity::TransactionValidityError;
 
 /// Descriptive error type
@@ -46,11 +47,7 @@ impl fmt::Display for Error {
 }
 
 #[cfg(feature = "std")]
-impl std::error::Error for Error {
-	fn source(&self) -> &str {
-		self.0
-	}
-}
+impl std::error::Error for Error {}
 
 impl From<&'static str> for Error {
 	#[cfg(feature = "std")]
@@ -76,10 +73,22 @@ impl From<TransactionValidityError> for Error {
 	}
 }
 
+impl From<codec::Error> for Error {
+	#[cfg(feature = "std")]
+	fn from(e: codec::Error) -> Error {
+		Error(e.what())
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn from(_e: codec::Error) -> Error {
+		Error
+	}
+}
+
 impl From<TryFromIntError> for Error {
 	#[cfg(feature = "std")]
-	fn from(t: TryFromIntError) -> Error {
-		Error(t.into())
+	fn from(_t: TryFromIntError) -> Error {
+		Error("Out of range integral type conversion attempted")
 	}
 
 	#[cfg(not(feature = "std"))]
@@ -87,3 +96,21 @@ impl From<TryFromIntError> for Error {
 		Error
 	}
 }
+
+impl From<PublicError> for Error {
+	#[cfg(feature = "std")]
+	fn from(e: PublicError) -> Error {
+		Error(match e {
+			PublicError::BadBase58 => "SS58 address is not valid base58",
+			PublicError::BadLength => "SS58 address has an invalid length",
+			PublicError::UnknownVersion => "SS58 address has an unknown network prefix",
+			PublicError::InvalidChecksum => "SS58 address has an invalid checksum",
+			_ => "Invalid SS58 address",
+		})
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn from(_e: PublicError) -> Error {
+		Error
+	}
+}
@@ -38,4 +38,7 @@ where
 {
 	const CALL: &'static str = "transfer";
 	const PALLET: &'static str = "Balances";
+	const ARGUMENTS: &'static [&'static str] = &["dest", "value"];
+	const ARGUMENT_TYPES: &'static [&'static str] =
+		&["<T::Lookup as StaticLookup>::Source", "Compact<T::Balance>"];
 }
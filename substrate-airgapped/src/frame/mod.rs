@@ -18,4 +18,9 @@ pub trait PalletCall {
 	const CALL: &'static str;
 	/// Name of the pallet, as it appears in metadata
 	const PALLET: &'static str;
+	/// Names of the call's arguments in the order they are encoded, as they appear in metadata
+	const ARGUMENTS: &'static [&'static str];
+	/// Types of the call's arguments in the order they are encoded, as their type names appear
+	/// in metadata before V14, e.g. `Compact<T::Balance>`
+	const ARGUMENT_TYPES: &'static [&'static str];
 }
@@ -1,6 +1,6 @@
 use super::Parameter;
-use codec::{Codec, Decode};
-use core::fmt::Debug;
+use codec::{Codec, Decode, Encode, HasCompact};
+use core::fmt::{self, Debug, Display};
 use sp_runtime::traits::AtLeast32Bit;
 
 /// Subset of the `pallet_system::Trait` the Runtime must implement.
@@ -45,3 +45,95 @@ pub trait System {
 		+ Decode
 		+ From<Self::AccountId>;
 }
+
+/// Multi-format address, the `Address` of runtimes that use `MultiAddress` as the source of their
+/// account lookup.
+///
+/// Same as `sp_runtime::MultiAddress`. Re-defined as it is not part of the sp-runtime version
+/// this crate depends on.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Debug)]
+pub enum MultiAddress<AccountId, AccountIndex: HasCompact> {
+	/// It's an account ID (pubkey).
+	Id(AccountId),
+	/// It's an account index.
+	Index(#[codec(compact)] AccountIndex),
+	/// It's some arbitrary raw bytes.
+	Raw(Vec<u8>),
+	/// It's a 32 byte representation.
+	Address32([u8; 32]),
+	/// Its a 20 byte representation.
+	Address20([u8; 20]),
+}
+
+impl<AccountId, AccountIndex: HasCompact> From<AccountId>
+	for MultiAddress<AccountId, AccountIndex>
+{
+	fn from(account_id: AccountId) -> Self {
+		MultiAddress::Id(account_id)
+	}
+}
+
+/// Address that may directly be an account, needed to verify a signature of its account.
+pub trait AsAccountId<AccountId> {
+	/// The account of the address, if it is given directly.
+	fn as_account_id(&self) -> Option<&AccountId>;
+}
+
+impl<AccountId, AccountIndex: HasCompact> AsAccountId<AccountId>
+	for MultiAddress<AccountId, AccountIndex>
+{
+	fn as_account_id(&self) -> Option<&AccountId> {
+		match self {
+			MultiAddress::Id(account_id) => Some(account_id),
+			_ => None,
+		}
+	}
+}
+
+impl<AccountId, AccountIndex> Display for MultiAddress<AccountId, AccountIndex>
+where
+	AccountId: Display,
+	AccountIndex: HasCompact + Display,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			MultiAddress::Id(account_id) => write!(f, "{}", account_id),
+			MultiAddress::Index(index) => write!(f, "Index({})", index),
+			MultiAddress::Raw(bytes) => write!(f, "Raw({:?})", bytes),
+			MultiAddress::Address32(bytes) => write!(f, "Address32({:?})", bytes),
+			MultiAddress::Address20(bytes) => write!(f, "Address20({:?})", bytes),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::AccountKeyring;
+	use sp_runtime::AccountId32;
+
+	type Address = MultiAddress<AccountId32, u32>;
+
+	#[test]
+	fn multi_address_encodes_each_variant() {
+		let bob = AccountKeyring::Bob.to_account_id();
+		let mut expected_id = vec![0];
+		expected_id.extend_from_slice(bob.as_ref());
+
+		let cases: Vec<(Address, Vec<u8>)> = vec![
+			(bob.into(), expected_id),
+			// Compact encoded index
+			(MultiAddress::Index(1), vec![1, 4]),
+			(MultiAddress::Index(1_000), vec![1, 161, 15]),
+			// Length prefixed bytes
+			(MultiAddress::Raw(vec![7, 8, 9]), vec![2, 12, 7, 8, 9]),
+			(MultiAddress::Address32([3; 32]), [&[3u8][..], &[3; 32][..]].concat()),
+			(MultiAddress::Address20([4; 20]), [&[4u8][..], &[4; 20][..]].concat()),
+		];
+
+		for (address, expected) in cases {
+			assert_eq!(address.encode(), expected);
+			assert_eq!(Address::decode(&mut &expected[..]).expect("test case works"), address);
+		}
+	}
+}
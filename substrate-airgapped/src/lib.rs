@@ -1,17 +1,31 @@
 //! Substrate airgapped transaction construction
 #![warn(missing_docs)]
 
+mod chain;
 mod error;
 mod frame;
 mod runtimes;
+mod signer;
 mod tx;
+pub mod vault;
 
 pub use crate::{
+	chain::{account_from_ss58, account_to_ss58, address_from_ss58, check_genesis_hash, Chain},
 	error::Error,
-	frame::{balances, system, PalletCall},
-	runtimes::KusamaRuntime,
+	frame::{balances, system, system::MultiAddress, PalletCall},
+	runtimes::{
+		KusamaRuntime, PolkadotRuntime, RococoRuntime, Runtime, StatemineRuntime, StatemintRuntime,
+		WestendRuntime,
+	},
+	signer::{AsyncSigner, PairSigner, Signer},
 	tx::{
-		tx_from_parts, CallIndex, GenericCall, MortalConfig, Mortality, SignedPayload, Tx,
-		TxConfig, UncheckedExtrinsic,
+		check_extrinsic, tx_from_parts, verify_extrinsic, AdditionalSignedData, AssetId, CallIndex,
+		DynamicExtra, DynamicSignedPayload, DynamicUncheckedExtrinsic, ExtrinsicChecks,
+		ExtrinsicReport, GenericCall, MortalConfig, Mortality, RawAdditionalSigned, RawArgs,
+		SignedPayload, SigningBytes, SupportedExtension, Tx, TxConfig, UncheckedExtrinsic,
+		MAX_UNHASHED_PAYLOAD_LEN,
 	},
 };
+
+#[cfg(feature = "std")]
+pub use crate::signer::{CommandSigner, FileSigner};
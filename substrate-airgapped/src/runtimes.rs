@@ -1,9 +1,14 @@
 use crate::{
-	frame::{balances::Balances, system::System},
-	tx::extra::{DefaultExtra, SignedExtra},
+	chain::Chain,
+	frame::{
+		balances::Balances,
+		system::{MultiAddress, System},
+	},
+	tx::extra::{AssetHubExtra, CurrentExtra, PolkadotExtra, SignedExtra},
 };
 use codec::{Decode, Encode};
 use core::fmt::Debug;
+use sp_core::H256;
 use sp_runtime::{
 	traits::{IdentifyAccount, Verify},
 	MultiSignature,
@@ -15,25 +20,351 @@ pub trait Runtime: System + Balances + Sized + Send + Sync + 'static {
 	type Signature: Verify + Encode + Debug + Decode + Eq + Send + Sync + Clone + 'static;
 	/// Transaction extras.
 	type Extra: SignedExtra<Self> + Send + Decode + Sync + 'static;
+	/// Chain the runtime is for. Transactions for a runtime with a chain must use the chain's
+	/// genesis hash.
+	const CHAIN: Option<Chain> = None;
+}
+
+/// Polkadot runtime specific types
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PolkadotRuntime;
+
+impl PolkadotRuntime {
+	/// Hash of the Polkadot genesis block.
+	pub const GENESIS_HASH: H256 = H256([
+		145, 177, 113, 187, 21, 142, 45, 56, 72, 250, 35, 169, 241, 194, 81, 130, 251, 142, 32, 49,
+		59, 44, 30, 180, 146, 25, 218, 122, 112, 206, 144, 195,
+	]);
+}
+
+impl Runtime for PolkadotRuntime {
+	type Signature = MultiSignature;
+	type Extra = PolkadotExtra<Self>;
+	const CHAIN: Option<Chain> = Some(Chain::Polkadot);
+}
+
+impl System for PolkadotRuntime {
+	type Index = u32;
+	type BlockNumber = u32;
+	type Hash = H256;
+	type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
+	type Address = MultiAddress<Self::AccountId, u32>;
+}
+
+impl Balances for PolkadotRuntime {
+	type Balance = u128;
 }
 
 /// Kusama runtime specific types
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct KusamaRuntime;
 
+impl KusamaRuntime {
+	/// Hash of the Kusama genesis block.
+	pub const GENESIS_HASH: H256 = H256([
+		176, 168, 212, 147, 40, 92, 45, 247, 50, 144, 223, 183, 230, 31, 135, 15, 23, 180, 24, 1,
+		25, 122, 20, 156, 169, 54, 84, 73, 158, 163, 218, 254,
+	]);
+}
+
 impl Runtime for KusamaRuntime {
 	type Signature = MultiSignature;
-	type Extra = DefaultExtra<Self>;
+	type Extra = CurrentExtra<Self>;
+	const CHAIN: Option<Chain> = Some(Chain::Kusama);
 }
 
 impl System for KusamaRuntime {
 	type Index = u32;
 	type BlockNumber = u32;
-	type Hash = sp_core::H256;
+	type Hash = H256;
 	type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
-	type Address = Self::AccountId;
+	type Address = MultiAddress<Self::AccountId, u32>;
 }
 
 impl Balances for KusamaRuntime {
 	type Balance = u128;
 }
+
+/// Westend runtime specific types
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WestendRuntime;
+
+impl WestendRuntime {
+	/// Hash of the Westend genesis block.
+	pub const GENESIS_HASH: H256 = H256([
+		225, 67, 242, 56, 3, 172, 80, 232, 246, 248, 230, 38, 149, 209, 206, 158, 78, 29, 104, 170,
+		54, 193, 205, 44, 253, 21, 52, 2, 19, 243, 66, 62,
+	]);
+}
+
+impl Runtime for WestendRuntime {
+	type Signature = MultiSignature;
+	type Extra = CurrentExtra<Self>;
+	const CHAIN: Option<Chain> = Some(Chain::Westend);
+}
+
+impl System for WestendRuntime {
+	type Index = u32;
+	type BlockNumber = u32;
+	type Hash = H256;
+	type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
+	type Address = MultiAddress<Self::AccountId, u32>;
+}
+
+impl Balances for WestendRuntime {
+	type Balance = u128;
+}
+
+/// Rococo runtime specific types
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RococoRuntime;
+
+impl RococoRuntime {
+	/// Hash of the Rococo genesis block.
+	pub const GENESIS_HASH: H256 = H256([
+		100, 8, 222, 119, 55, 197, 156, 35, 136, 144, 83, 58, 242, 88, 150, 162, 194, 6, 8, 216,
+		179, 128, 187, 1, 2, 154, 203, 57, 39, 129, 6, 62,
+	]);
+}
+
+impl Runtime for RococoRuntime {
+	type Signature = MultiSignature;
+	type Extra = CurrentExtra<Self>;
+	const CHAIN: Option<Chain> = Some(Chain::Rococo);
+}
+
+impl System for RococoRuntime {
+	type Index = u32;
+	type BlockNumber = u32;
+	type Hash = H256;
+	type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
+	type Address = MultiAddress<Self::AccountId, u32>;
+}
+
+impl Balances for RococoRuntime {
+	type Balance = u128;
+}
+
+/// Statemint, the asset hub of Polkadot, runtime specific types
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StatemintRuntime;
+
+impl StatemintRuntime {
+	/// Hash of the Statemint genesis block.
+	pub const GENESIS_HASH: H256 = H256([
+		104, 213, 111, 21, 248, 93, 49, 54, 151, 14, 193, 105, 70, 4, 11, 193, 117, 38, 84, 233, 6,
+		20, 127, 126, 67, 233, 213, 57, 215, 195, 222, 47,
+	]);
+}
+
+impl Runtime for StatemintRuntime {
+	type Signature = MultiSignature;
+	type Extra = AssetHubExtra<Self, u32>;
+	const CHAIN: Option<Chain> = Some(Chain::Statemint);
+}
+
+impl System for StatemintRuntime {
+	type Index = u32;
+	type BlockNumber = u32;
+	type Hash = H256;
+	type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
+	type Address = MultiAddress<Self::AccountId, u32>;
+}
+
+impl Balances for StatemintRuntime {
+	type Balance = u128;
+}
+
+/// Statemine, the asset hub of Kusama, runtime specific types
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StatemineRuntime;
+
+impl StatemineRuntime {
+	/// Hash of the Statemine genesis block.
+	pub const GENESIS_HASH: H256 = H256([
+		72, 35, 158, 246, 7, 215, 146, 136, 116, 2, 122, 67, 166, 118, 137, 32, 151, 39, 223, 179,
+		211, 220, 94, 91, 3, 163, 155, 220, 46, 218, 119, 26,
+	]);
+}
+
+impl Runtime for StatemineRuntime {
+	type Signature = MultiSignature;
+	type Extra = AssetHubExtra<Self, u32>;
+	const CHAIN: Option<Chain> = Some(Chain::Statemine);
+}
+
+impl System for StatemineRuntime {
+	type Index = u32;
+	type BlockNumber = u32;
+	type Hash = H256;
+	type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
+	type Address = MultiAddress<Self::AccountId, u32>;
+}
+
+impl Balances for StatemineRuntime {
+	type Balance = u128;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{balances::Transfer, CallIndex, GenericCall, Mortality, Tx, TxConfig};
+	use sp_core::crypto::AccountId32;
+	use sp_core::ed25519;
+	use sp_keyring::{AccountKeyring, Ed25519Keyring};
+	use sp_runtime::MultiSigner;
+
+	const ALICE_ED25519: &str = "88dc3417d5058ec4b4503e0c12ea1a0a89be200fe98922423d4334014fa6b0ee";
+	type Address = MultiAddress<AccountId32, u32>;
+
+	const BOB: &str = "8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48";
+
+	/// Transfer of 12 from Alice's ed25519 account to Bob, so the signature is deterministic.
+	fn transfer_tx<R>(
+		balances_index: u8,
+		spec_version: u32,
+		tx_version: u32,
+		genesis_hash: H256,
+	) -> Tx<Transfer<R>, R>
+	where
+		R: Runtime + System<AccountId = AccountId32, Address = Address, Index = u32, Hash = H256>,
+		R: Balances<Balance = u128> + Clone + Eq + Debug,
+	{
+		let alice = MultiSigner::from(Ed25519Keyring::Alice.public()).into_account();
+		let transfer = Transfer { to: AccountKeyring::Bob.to_account_id().into(), amount: 12 };
+
+		Tx::new(TxConfig {
+			call: GenericCall::new(CallIndex::new(balances_index, 0), transfer),
+			address: alice.into(),
+			nonce: 0,
+			tx_version,
+			spec_version,
+			genesis_hash,
+			mortality: Mortality::Immortal,
+			tip: 0,
+			fee_asset: None,
+			metadata_hash: None,
+		})
+		.expect("test case works")
+	}
+
+	/// Assert the `SignedPayload` of `tx` and the `UncheckedExtrinsic` signed by Alice's ed25519 key
+	/// encode to the expected bytes. The payload ends with the `None` metadata hash of the disabled
+	/// `CheckMetadataHash`.
+	fn assert_transfer_encodes<R>(tx: Tx<Transfer<R>, R>, payload: &[&str], signature: &str)
+	where
+		R: Runtime + System<AccountId = AccountId32, Address = Address, Index = u32, Hash = H256>,
+		R: Balances<Balance = u128> + Clone + Eq + Debug,
+		R::Signature: From<ed25519::Signature>,
+	{
+		let signed_payload = tx.signed_payload().expect("test case works");
+		assert_eq!(hex::encode(signed_payload.encode()), payload.concat());
+
+		let extrinsic =
+			tx.signed_tx_from_pair(Ed25519Keyring::Alice.pair()).expect("test case works");
+		let expected = [
+			// Length, version and Alice's ed25519 account as `MultiAddress::Id`
+			"2d02",
+			"84",
+			"00",
+			ALICE_ED25519,
+			// `MultiSignature::Ed25519`
+			"00",
+			signature,
+			// Extra: immortal era, nonce, tip and disabled `CheckMetadataHash`
+			"00000000",
+			payload[0],
+			payload[1],
+			payload[2],
+			payload[3],
+		];
+		assert_eq!(hex::encode(extrinsic.encode()), expected.concat());
+	}
+
+	#[test]
+	fn polkadot_transfer_encodes() {
+		let tx = transfer_tx::<PolkadotRuntime>(5, 26, 5, PolkadotRuntime::GENESIS_HASH);
+		let genesis_hash = "91b171bb158e2d3848fa23a9f1c25182fb8e20313b2c1eb49219da7a70ce90c3";
+		// `PrevalidateAttests` adds neither extra nor additional signed bytes
+		let payload = [
+			"0500",
+			"00",
+			BOB,
+			"30",
+			"00000000",
+			"1a000000",
+			"05000000",
+			genesis_hash,
+			genesis_hash,
+			"00",
+		];
+		let signature = "44ba169ae857d0fe1496a7270408ec7a41203ef27a249773b363708e62d10ee847e7a604a98b074155654edc4aeafc639cd55f91ef1d679344ce1e0a59517e05";
+
+		assert_transfer_encodes(tx, &payload, signature);
+	}
+
+	#[test]
+	fn kusama_transfer_encodes() {
+		let tx = transfer_tx::<KusamaRuntime>(4, 2026, 4, KusamaRuntime::GENESIS_HASH);
+		let genesis_hash = "b0a8d493285c2df73290dfb7e61f870f17b41801197a149ca93654499ea3dafe";
+		let payload = [
+			"0400",
+			"00",
+			BOB,
+			"30",
+			"00000000",
+			"ea070000",
+			"04000000",
+			genesis_hash,
+			genesis_hash,
+			"00",
+		];
+		let signature = "b16e9c5882e5cce10ecd99b608d25d08d9ad0bf89df4eb4aecd1ffaae2a48ab69d5a6d6b5618a69394dd1516c5f02c77eca69e33088aeca1a40b1b9be5e83f02";
+
+		assert_transfer_encodes(tx, &payload, signature);
+	}
+
+	#[test]
+	fn westend_transfer_encodes() {
+		let tx = transfer_tx::<WestendRuntime>(4, 46, 4, WestendRuntime::GENESIS_HASH);
+		let genesis_hash = "e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e";
+		let payload = [
+			"0400",
+			"00",
+			BOB,
+			"30",
+			"00000000",
+			"2e000000",
+			"04000000",
+			genesis_hash,
+			genesis_hash,
+			"00",
+		];
+		let signature = "37b994cdfea1c2f26420a7fb1206c78d321ea08a32f3e082460917738fdadff5069a4f35886f4392cd3f3a3f12fe682eee78a1abf588a0325e7bc95d3620e104";
+
+		assert_transfer_encodes(tx, &payload, signature);
+	}
+
+	#[test]
+	fn rococo_transfer_encodes() {
+		// Spec and transaction version of the Rococo runtime whose metadata the metadata crate's
+		// fixtures hold, which decodes this payload
+		let tx = transfer_tx::<RococoRuntime>(4, 1_006_002, 24, RococoRuntime::GENESIS_HASH);
+		let genesis_hash = "6408de7737c59c238890533af25896a2c20608d8b380bb01029acb392781063e";
+		let payload = [
+			"0400",
+			"00",
+			BOB,
+			"30",
+			"00000000",
+			"b2590f00",
+			"18000000",
+			genesis_hash,
+			genesis_hash,
+			"00",
+		];
+		let signature = "a21dd9a71eb005976120a9d7a8a4ba6fd5a33cf099a75038ab7c25160da9f8b2e02b1b8797b403d56eba945a4d8bcc1cec7ba4491e40cc0c14ccbc631332bb0c";
+
+		assert_transfer_encodes(tx, &payload, signature);
+	}
+}
@@ -0,0 +1,331 @@
+use crate::{runtimes::Runtime, Error};
+use core::future::Future;
+use sp_core::Pair;
+use sp_runtime::traits::{IdentifyAccount, Verify};
+#[cfg(feature = "std")]
+use {
+	codec::DecodeAll,
+	std::{
+		fs,
+		io::Write,
+		path::PathBuf,
+		process::{Command, Stdio},
+		thread,
+		time::{Duration, Instant},
+	},
+};
+
+/// Signs payloads for an account of runtime `R`, e.g. with a keyring pair, a hardware security
+/// module or an offline device.
+pub trait Signer<R: Runtime> {
+	/// Sign the bytes of a `SignedPayload`, which are already hashed if the payload is longer than
+	/// 256 bytes. Returns the address of the signer and the signature.
+	fn sign(&self, payload: &[u8]) -> Result<(R::Address, R::Signature), Error>;
+}
+
+/// Signs payloads for an account of runtime `R` asynchronously, for signers that wait on another
+/// process or device without blocking. Every `Signer` is an `AsyncSigner`.
+pub trait AsyncSigner<R: Runtime> {
+	/// Sign the bytes of a `SignedPayload`, which are already hashed if the payload is longer than
+	/// 256 bytes. Returns the address of the signer and the signature.
+	fn sign_async(
+		&self,
+		payload: &[u8],
+	) -> impl Future<Output = Result<(R::Address, R::Signature), Error>>;
+}
+
+impl<R: Runtime, S: Signer<R>> AsyncSigner<R> for S {
+	fn sign_async(
+		&self,
+		payload: &[u8],
+	) -> impl Future<Output = Result<(R::Address, R::Signature), Error>> {
+		core::future::ready(self.sign(payload))
+	}
+}
+
+/// `Signer` with a keyring pair in the same process.
+#[derive(Clone)]
+pub struct PairSigner<R: Runtime, P: Pair> {
+	pair: P,
+	address: R::Address,
+}
+
+impl<R, P> PairSigner<R, P>
+where
+	R: Runtime,
+	P: Pair,
+	<R::Signature as Verify>::Signer: From<P::Public> + IdentifyAccount<AccountId = R::AccountId>,
+{
+	/// Create a `PairSigner` for the account of the given pair.
+	pub fn new(pair: P) -> Self {
+		let account = <R::Signature as Verify>::Signer::from(pair.public()).into_account();
+
+		Self { pair, address: account.into() }
+	}
+}
+
+impl<R, P> Signer<R> for PairSigner<R, P>
+where
+	R: Runtime,
+	P: Pair,
+	R::Signature: From<P::Signature>,
+{
+	fn sign(&self, payload: &[u8]) -> Result<(R::Address, R::Signature), Error> {
+		Ok((self.address.clone(), self.pair.sign(payload).into()))
+	}
+}
+
+/// `Signer` that exchanges files with another process or an offline device: the hex of the
+/// payload is written to a payload file, then it waits for a signature file with the hex of the
+/// SCALE encoded signature.
+///
+/// The signature file is removed before the payload is written, so a stale signature is never
+/// used. It should be created atomically, e.g. by renaming it into place once written.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct FileSigner<R: Runtime> {
+	address: R::Address,
+	payload_path: PathBuf,
+	signature_path: PathBuf,
+	poll_interval: Duration,
+	timeout: Option<Duration>,
+}
+
+#[cfg(feature = "std")]
+impl<R: Runtime> FileSigner<R> {
+	/// Create a `FileSigner` for the given address, which waits for a signature without a
+	/// timeout, checking for the signature file every 100 milliseconds.
+	pub fn new(address: R::Address, payload_path: PathBuf, signature_path: PathBuf) -> Self {
+		Self {
+			address,
+			payload_path,
+			signature_path,
+			poll_interval: Duration::from_millis(100),
+			timeout: None,
+		}
+	}
+
+	/// Stop waiting for the signature file after the given duration.
+	pub fn with_timeout(self, timeout: Duration) -> Self {
+		Self { timeout: Some(timeout), ..self }
+	}
+
+	/// Check for the signature file at the given interval.
+	pub fn with_poll_interval(self, poll_interval: Duration) -> Self {
+		Self { poll_interval, ..self }
+	}
+}
+
+#[cfg(feature = "std")]
+impl<R: Runtime> Signer<R> for FileSigner<R> {
+	fn sign(&self, payload: &[u8]) -> Result<(R::Address, R::Signature), Error> {
+		if self.signature_path.exists() {
+			fs::remove_file(&self.signature_path)
+				.map_err(|_| "Failed to remove the previous signature file")?;
+		}
+		fs::write(&self.payload_path, format!("0x{}\n", hex::encode(payload)))
+			.map_err(|_| "Failed to write the payload file")?;
+
+		let start = Instant::now();
+		while !self.signature_path.exists() {
+			if self.timeout.is_some_and(|timeout| start.elapsed() >= timeout) {
+				return Err("Timed out waiting for the signature file".into());
+			}
+			thread::sleep(self.poll_interval);
+		}
+		let signature = fs::read_to_string(&self.signature_path)
+			.map_err(|_| "Failed to read the signature file")?;
+
+		Ok((self.address.clone(), decode_signature::<R>(&signature)?))
+	}
+}
+
+/// `Signer` that runs a command, e.g. the client of a hardware security module, which is given
+/// the hex of the payload as a line on stdin and outputs the hex of the SCALE encoded signature
+/// on stdout.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct CommandSigner<R: Runtime> {
+	address: R::Address,
+	program: String,
+	args: Vec<String>,
+}
+
+#[cfg(feature = "std")]
+impl<R: Runtime> CommandSigner<R> {
+	/// Create a `CommandSigner` for the given address, running the program with the arguments.
+	pub fn new(address: R::Address, program: String, args: Vec<String>) -> Self {
+		Self { address, program, args }
+	}
+}
+
+#[cfg(feature = "std")]
+impl<R: Runtime> Signer<R> for CommandSigner<R> {
+	fn sign(&self, payload: &[u8]) -> Result<(R::Address, R::Signature), Error> {
+		let mut child = Command::new(&self.program)
+			.args(&self.args)
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.spawn()
+			.map_err(|_| "Failed to run the signer command")?;
+		child
+			.stdin
+			.take()
+			.ok_or("Failed to open stdin of the signer command")?
+			.write_all(format!("0x{}\n", hex::encode(payload)).as_bytes())
+			.map_err(|_| "Failed to write the payload to the signer command")?;
+
+		let output = child.wait_with_output().map_err(|_| "Failed to run the signer command")?;
+		if !output.status.success() {
+			return Err("Signer command failed".into());
+		}
+		let signature = String::from_utf8(output.stdout)
+			.map_err(|_| "Signer command output is not valid UTF-8")?;
+
+		Ok((self.address.clone(), decode_signature::<R>(&signature)?))
+	}
+}
+
+/// Decode the hex of a SCALE encoded signature.
+#[cfg(feature = "std")]
+pub(crate) fn decode_signature<R: Runtime>(signature: &str) -> Result<R::Signature, Error> {
+	let signature = signature.trim();
+	let bytes = hex::decode(signature.strip_prefix("0x").unwrap_or(signature))
+		.map_err(|_| "Signature is not valid hex")?;
+
+	R::Signature::decode_all(&bytes).map_err(|_| "Failed to decode the signature".into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		balances::Transfer, CallIndex, GenericCall, KusamaRuntime, Mortality, RawArgs, Tx, TxConfig,
+	};
+	use codec::Encode;
+	use core::{
+		pin::pin,
+		task::{Context, Poll, Waker},
+	};
+	use sp_core::ed25519;
+	use sp_keyring::{AccountKeyring, Ed25519Keyring};
+	use sp_runtime::MultiSignature;
+
+	fn transfer_tx(sender: sp_runtime::AccountId32) -> Tx<RawArgs, KusamaRuntime> {
+		let transfer = Transfer::<KusamaRuntime> {
+			to: AccountKeyring::Bob.to_account_id().into(),
+			amount: 12,
+		};
+
+		Tx::new(TxConfig {
+			call: GenericCall::new(CallIndex::new(4, 0), RawArgs(transfer.encode())),
+			address: sender.into(),
+			nonce: 0,
+			tx_version: 4,
+			spec_version: 2026,
+			genesis_hash: KusamaRuntime::GENESIS_HASH,
+			mortality: Mortality::Immortal,
+			tip: 0,
+			fee_asset: None,
+			metadata_hash: None,
+		})
+		.expect("test case works")
+	}
+
+	fn signature_hex(payload: &str) -> String {
+		let payload =
+			hex::decode(payload.trim().trim_start_matches("0x")).expect("test case works");
+		let signature = MultiSignature::from(Ed25519Keyring::Alice.pair().sign(&payload));
+
+		format!("0x{}\n", hex::encode(signature.encode()))
+	}
+
+	#[test]
+	fn pair_signer_works() {
+		let tx = transfer_tx(Ed25519Keyring::Alice.to_account_id());
+		let signer = PairSigner::<KusamaRuntime, ed25519::Pair>::new(Ed25519Keyring::Alice.pair());
+
+		assert_eq!(
+			tx.signed_tx(&signer).expect("test case works"),
+			tx.signed_tx_from_pair(Ed25519Keyring::Alice.pair()).expect("test case works")
+		);
+
+		// Signed for another account than the transaction's sender
+		let signer = PairSigner::<KusamaRuntime, ed25519::Pair>::new(Ed25519Keyring::Bob.pair());
+		assert!(tx.signed_tx(&signer).is_err());
+	}
+
+	#[test]
+	fn async_signer_works() {
+		let tx = transfer_tx(Ed25519Keyring::Alice.to_account_id());
+		let signer = PairSigner::<KusamaRuntime, ed25519::Pair>::new(Ed25519Keyring::Alice.pair());
+
+		let mut context = Context::from_waker(Waker::noop());
+		let signed = match pin!(tx.signed_tx_async(&signer)).poll(&mut context) {
+			Poll::Ready(signed) => signed.expect("test case works"),
+			Poll::Pending => panic!("Signing with a pair is ready"),
+		};
+		assert_eq!(signed, tx.signed_tx(&signer).expect("test case works"));
+	}
+
+	#[test]
+	fn file_signer_works() {
+		let dir = std::env::temp_dir().join(format!("file-signer-{}", std::process::id()));
+		fs::create_dir_all(&dir).expect("test case works");
+		let (payload_path, signature_path) = (dir.join("payload"), dir.join("signature"));
+		fs::write(&signature_path, "stale").expect("test case works");
+
+		let device = {
+			let (payload_path, signature_path) = (payload_path.clone(), signature_path.clone());
+			thread::spawn(move || {
+				while !payload_path.exists() {
+					thread::sleep(Duration::from_millis(10));
+				}
+				let payload = fs::read_to_string(&payload_path).expect("test case works");
+				fs::write(&signature_path, signature_hex(&payload)).expect("test case works");
+			})
+		};
+
+		let tx = transfer_tx(Ed25519Keyring::Alice.to_account_id());
+		let signer = FileSigner::<KusamaRuntime>::new(
+			tx.address().clone(),
+			payload_path.clone(),
+			signature_path.clone(),
+		)
+		.with_poll_interval(Duration::from_millis(10));
+		let signed = tx.signed_tx(&signer).expect("test case works");
+		device.join().expect("test case works");
+		assert_eq!(
+			signed,
+			tx.signed_tx_from_pair(Ed25519Keyring::Alice.pair()).expect("test case works")
+		);
+
+		// No signature file
+		fs::remove_file(&signature_path).expect("test case works");
+		let signer = signer.with_timeout(Duration::from_millis(50));
+		assert!(tx.signed_tx(&signer).is_err());
+		fs::remove_dir_all(&dir).expect("test case works");
+	}
+
+	#[test]
+	fn command_signer_works() {
+		let tx = transfer_tx(Ed25519Keyring::Alice.to_account_id());
+		let signature = tx
+			.signed_payload()
+			.expect("test case works")
+			.using_encoded(|payload| signature_hex(&hex::encode(payload)));
+
+		let command = |script: String| {
+			let args = vec!["-c".to_owned(), script];
+			CommandSigner::<KusamaRuntime>::new(tx.address().clone(), "sh".to_owned(), args)
+		};
+		let signer = command(format!("read payload; echo {}", signature));
+		assert_eq!(
+			tx.signed_tx(&signer).expect("test case works"),
+			tx.signed_tx_from_pair(Ed25519Keyring::Alice.pair()).expect("test case works")
+		);
+
+		assert!(tx.signed_tx(&command("read payload; echo 0x1234".to_owned())).is_err());
+		assert!(tx.signed_tx(&command("exit 1".to_owned())).is_err());
+	}
+}
@@ -0,0 +1,427 @@
+use super::extra::{
+	AssetId, ChargeAssetTxPayment, ChargeTransactionPayment, CheckEra, CheckGenesis,
+	CheckMetadataHash, CheckMortality, CheckNonZeroSender, CheckNonce, CheckSpecVersion,
+	CheckTxVersion, CheckWeight, PrevalidateAttests,
+};
+use crate::{
+	frame::{balances::Balances, system::System},
+	Error,
+};
+use codec::{Decode, Encode, Input, Output};
+use core::{fmt::Debug, marker::PhantomData};
+use sp_runtime::{
+	generic::Era, traits::SignedExtension, transaction_validity::TransactionValidityError,
+};
+
+/// Transaction extras built from the identifiers of a runtime's signed extensions, e.g. the
+/// `signed_extensions` of its metadata, instead of the fixed `Extra` of a `Runtime`.
+///
+/// Encodes to the extra data of the extensions, in the order of the identifiers. As that depends
+/// on the identifiers, its `Decode`, which every `SignedExtension` requires, always errors. A
+/// `DynamicExtra` is rebuilt from the raw bytes of its extra and additional signed data with
+/// `from_raw` instead, e.g. after decoding them with the type registry of the runtime metadata.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DynamicExtra {
+	signed_extensions: Vec<String>,
+	extra: Vec<u8>,
+	additional_signed: RawAdditionalSigned,
+}
+
+/// Signed extensions `DynamicExtra` can be built from, the table of identifiers that transactions
+/// with the signed extensions of metadata are built and decoded with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SupportedExtension {
+	/// `CheckSpecVersion`, with the spec version as additional signed data.
+	CheckSpecVersion,
+	/// `CheckTxVersion`, with the transaction version as additional signed data.
+	CheckTxVersion,
+	/// `CheckGenesis`, with the genesis hash as additional signed data.
+	CheckGenesis,
+	/// `CheckEra`, with the era as extra and the checkpoint block hash as additional signed data.
+	CheckEra,
+	/// `CheckMortality`, the same as `CheckEra` in newer runtimes.
+	CheckMortality,
+	/// `CheckNonce`, with the compact nonce as extra.
+	CheckNonce,
+	/// `CheckWeight`, without data.
+	CheckWeight,
+	/// `ChargeTransactionPayment`, with the compact tip as extra.
+	ChargeTransactionPayment,
+	/// `ChargeAssetTxPayment`, with the compact tip and the optional asset id to pay fees in as
+	/// extra.
+	ChargeAssetTxPayment,
+	/// `PrevalidateAttests`, without data.
+	PrevalidateAttests,
+	/// `CheckNonZeroSender`, without data.
+	CheckNonZeroSender,
+	/// `CheckMetadataHash`, with the mode as extra and the optional metadata hash as additional
+	/// signed data.
+	CheckMetadataHash,
+}
+
+impl SupportedExtension {
+	/// All signed extensions `DynamicExtra` can be built from.
+	pub const ALL: &'static [Self] = &[
+		SupportedExtension::CheckSpecVersion,
+		SupportedExtension::CheckTxVersion,
+		SupportedExtension::CheckGenesis,
+		SupportedExtension::CheckEra,
+		SupportedExtension::CheckMortality,
+		SupportedExtension::CheckNonce,
+		SupportedExtension::CheckWeight,
+		SupportedExtension::ChargeTransactionPayment,
+		SupportedExtension::ChargeAssetTxPayment,
+		SupportedExtension::PrevalidateAttests,
+		SupportedExtension::CheckNonZeroSender,
+		SupportedExtension::CheckMetadataHash,
+	];
+
+	/// Identifier of the signed extension, as listed in metadata.
+	pub fn identifier(self) -> &'static str {
+		match self {
+			SupportedExtension::CheckSpecVersion => "CheckSpecVersion",
+			SupportedExtension::CheckTxVersion => "CheckTxVersion",
+			SupportedExtension::CheckGenesis => "CheckGenesis",
+			SupportedExtension::CheckEra => "CheckEra",
+			SupportedExtension::CheckMortality => "CheckMortality",
+			SupportedExtension::CheckNonce => "CheckNonce",
+			SupportedExtension::CheckWeight => "CheckWeight",
+			SupportedExtension::ChargeTransactionPayment => "ChargeTransactionPayment",
+			SupportedExtension::ChargeAssetTxPayment => "ChargeAssetTxPayment",
+			SupportedExtension::PrevalidateAttests => "PrevalidateAttests",
+			SupportedExtension::CheckNonZeroSender => "CheckNonZeroSender",
+			SupportedExtension::CheckMetadataHash => "CheckMetadataHash",
+		}
+	}
+
+	/// The supported signed extension with `identifier`, if there is one.
+	pub fn from_identifier(identifier: &str) -> Option<Self> {
+		Self::ALL.iter().copied().find(|extension| extension.identifier() == identifier)
+	}
+}
+
+/// SCALE encoded additional signed data of `DynamicExtra`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RawAdditionalSigned(pub Vec<u8>);
+
+impl DynamicExtra {
+	/// Create a `DynamicExtra` for the given signed extensions.
+	///
+	/// Errors if an extension is not a `SupportedExtension`, as the data of the
+	/// transaction would not match what the runtime expects and its signature would be invalid.
+	/// Also errors if a fee asset is given but there is no `ChargeAssetTxPayment` to pay it with,
+	/// or a metadata hash is given but there is no `CheckMetadataHash` to include it.
+	#[allow(clippy::too_many_arguments)]
+	pub fn new<T, S>(
+		signed_extensions: &[S],
+		spec_version: u32,
+		tx_version: u32,
+		nonce: T::Index,
+		genesis_hash: T::Hash,
+		era_info: (Era, Option<T::Hash>),
+		tip: T::Balance,
+		fee_asset: Option<AssetId>,
+		metadata_hash: Option<[u8; 32]>,
+	) -> Result<Self, Error>
+	where
+		T: System + Balances + Clone + Debug + Eq + Send + Sync,
+		S: AsRef<str>,
+	{
+		let era_hash = if let Some(hash) = era_info.1 { hash } else { genesis_hash };
+		let mut dynamic_extra = DynamicExtra {
+			signed_extensions: Vec::with_capacity(signed_extensions.len()),
+			extra: Vec::new(),
+			additional_signed: RawAdditionalSigned(Vec::new()),
+		};
+
+		for identifier in signed_extensions {
+			let extension = SupportedExtension::from_identifier(identifier.as_ref())
+				.ok_or("Unknown signed extension")?;
+			match extension {
+				SupportedExtension::CheckSpecVersion => {
+					dynamic_extra.push(CheckSpecVersion::<T>(PhantomData, spec_version))?
+				}
+				SupportedExtension::CheckTxVersion => {
+					dynamic_extra.push(CheckTxVersion::<T>(PhantomData, tx_version))?
+				}
+				SupportedExtension::CheckGenesis => {
+					dynamic_extra.push(CheckGenesis::<T>(PhantomData, genesis_hash))?
+				}
+				SupportedExtension::CheckEra => {
+					dynamic_extra.push(CheckEra::<T>((era_info.0, PhantomData), era_hash))?
+				}
+				SupportedExtension::CheckMortality => {
+					dynamic_extra.push(CheckMortality::<T>((era_info.0, PhantomData), era_hash))?
+				}
+				SupportedExtension::CheckNonce => dynamic_extra.push(CheckNonce::<T>(nonce))?,
+				SupportedExtension::CheckWeight => {
+					dynamic_extra.push(CheckWeight::<T>(PhantomData))?
+				}
+				SupportedExtension::ChargeTransactionPayment => {
+					dynamic_extra.push(ChargeTransactionPayment::<T>(tip))?
+				}
+				SupportedExtension::ChargeAssetTxPayment => {
+					dynamic_extra.push(ChargeAssetTxPayment::<T>::new(tip, fee_asset.clone()))?
+				}
+				SupportedExtension::PrevalidateAttests => {
+					dynamic_extra.push(PrevalidateAttests::<T>(PhantomData))?
+				}
+				SupportedExtension::CheckNonZeroSender => {
+					dynamic_extra.push(CheckNonZeroSender::<T>(PhantomData))?
+				}
+				SupportedExtension::CheckMetadataHash => {
+					dynamic_extra.push(CheckMetadataHash::new(metadata_hash))?
+				}
+			}
+			dynamic_extra.signed_extensions.push(identifier.as_ref().to_owned());
+		}
+
+		if fee_asset.is_some()
+			&& !dynamic_extra.signed_extensions.iter().any(|id| id == "ChargeAssetTxPayment")
+		{
+			return Err("Fee asset requires the ChargeAssetTxPayment signed extension".into());
+		}
+		if metadata_hash.is_some()
+			&& !dynamic_extra.signed_extensions.iter().any(|id| id == "CheckMetadataHash")
+		{
+			return Err("Metadata hash requires the CheckMetadataHash signed extension".into());
+		}
+
+		Ok(dynamic_extra)
+	}
+
+	/// Create a `DynamicExtra` from the SCALE encoded extra and additional signed data of a payload,
+	/// e.g. one created on another machine, without knowing the identifiers of its signed
+	/// extensions, so `signed_extensions` is empty.
+	pub fn from_raw(extra: Vec<u8>, additional_signed: Vec<u8>) -> Self {
+		DynamicExtra {
+			signed_extensions: Vec::new(),
+			extra,
+			additional_signed: RawAdditionalSigned(additional_signed),
+		}
+	}
+
+	/// Identifiers of the signed extensions, in the order their data is encoded.
+	pub fn signed_extensions(&self) -> &[String] {
+		&self.signed_extensions
+	}
+
+	fn push<E: SignedExtension>(&mut self, extension: E) -> Result<(), Error> {
+		extension.encode_to(&mut self.extra);
+		extension.additional_signed()?.encode_to(&mut self.additional_signed.0);
+
+		Ok(())
+	}
+}
+
+impl Encode for DynamicExtra {
+	fn size_hint(&self) -> usize {
+		self.extra.len()
+	}
+
+	fn encode_to<T: Output>(&self, dest: &mut T) {
+		dest.write(&self.extra)
+	}
+}
+
+impl Decode for DynamicExtra {
+	fn decode<I: Input>(_input: &mut I) -> Result<Self, codec::Error> {
+		Err("DynamicExtra can not be decoded without its signed extensions".into())
+	}
+}
+
+impl Encode for RawAdditionalSigned {
+	fn size_hint(&self) -> usize {
+		self.0.len()
+	}
+
+	fn encode_to<T: Output>(&self, dest: &mut T) {
+		dest.write(&self.0)
+	}
+}
+
+impl SignedExtension for DynamicExtra {
+	const IDENTIFIER: &'static str = "DynamicExtra";
+	type AccountId = u64;
+	type Call = ();
+	type AdditionalSigned = RawAdditionalSigned;
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		Ok(self.additional_signed.clone())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		runtimes::Runtime, tx::extra::SignedExtra, Chain, KusamaRuntime, PolkadotRuntime,
+		StatemineRuntime, WestendRuntime,
+	};
+	use sp_core::H256;
+
+	fn dynamic_extra<S: AsRef<str>>(
+		signed_extensions: &[S],
+		genesis_hash: H256,
+		fee_asset: Option<AssetId>,
+	) -> Result<DynamicExtra, Error> {
+		let era_info = (Era::mortal(64, 100), Some(H256::repeat_byte(1)));
+		DynamicExtra::new::<KusamaRuntime, _>(
+			signed_extensions,
+			2026,
+			4,
+			7,
+			genesis_hash,
+			era_info,
+			1_000,
+			fee_asset,
+			None,
+		)
+	}
+
+	fn assert_matches_runtime_extra<R>(chain: Chain)
+	where
+		R: Runtime + System<Index = u32, Hash = H256> + Balances<Balance = u128>,
+		R: Clone + Debug + Eq,
+	{
+		let era_info = (Era::mortal(64, 100), Some(H256::repeat_byte(1)));
+		let fee_asset = Some(AssetId::from(1984))
+			.filter(|_| chain.signed_extensions().contains(&"ChargeAssetTxPayment"));
+		let genesis_hash = chain.genesis_hash();
+		let metadata_hash = Some([2; 32]);
+		let extra = R::Extra::new(
+			2026,
+			4,
+			7,
+			genesis_hash,
+			era_info,
+			1_000,
+			fee_asset.clone(),
+			metadata_hash,
+		)
+		.extra();
+		let dynamic_extra = DynamicExtra::new::<R, _>(
+			&chain.signed_extensions(),
+			2026,
+			4,
+			7,
+			genesis_hash,
+			era_info,
+			1_000,
+			fee_asset,
+			metadata_hash,
+		)
+		.expect("test case works");
+
+		assert_eq!(dynamic_extra.encode(), extra.encode());
+		assert_eq!(
+			dynamic_extra.additional_signed().expect("test case works").encode(),
+			extra.additional_signed().expect("test case works").encode()
+		);
+	}
+
+	#[test]
+	fn dynamic_extra_matches_the_extra_of_runtimes() {
+		assert_matches_runtime_extra::<KusamaRuntime>(Chain::Kusama);
+		assert_matches_runtime_extra::<PolkadotRuntime>(Chain::Polkadot);
+		assert_matches_runtime_extra::<WestendRuntime>(Chain::Westend);
+		assert_matches_runtime_extra::<StatemineRuntime>(Chain::Statemine);
+	}
+
+	#[test]
+	fn dynamic_extra_follows_the_order_of_the_signed_extensions() {
+		let genesis_hash = H256::repeat_byte(2);
+		let dynamic_extra = dynamic_extra(
+			&["CheckNonZeroSender", "CheckMortality", "ChargeTransactionPayment", "CheckGenesis"],
+			genesis_hash,
+			None,
+		)
+		.expect("test case works");
+
+		let mut extra = Era::mortal(64, 100).encode();
+		extra.extend(&[0xa1, 0x0f]);
+		assert_eq!(dynamic_extra.encode(), extra);
+		let additional_signed = [H256::repeat_byte(1).as_bytes(), genesis_hash.as_bytes()].concat();
+		assert_eq!(
+			dynamic_extra.additional_signed().expect("test case works").0,
+			additional_signed
+		);
+		assert_eq!(dynamic_extra.signed_extensions().len(), 4);
+	}
+
+	#[test]
+	fn dynamic_extra_from_raw_keeps_the_bytes() {
+		let dynamic_extra =
+			dynamic_extra(&Chain::Statemine.signed_extensions(), H256::zero(), None)
+				.expect("test case works");
+		let additional_signed = dynamic_extra.additional_signed().expect("test case works").0;
+		let raw = DynamicExtra::from_raw(dynamic_extra.encode(), additional_signed.clone());
+
+		assert_eq!(raw.encode(), dynamic_extra.encode());
+		assert_eq!(raw.additional_signed().expect("test case works").0, additional_signed);
+		assert!(raw.signed_extensions().is_empty());
+	}
+
+	#[test]
+	fn dynamic_extra_rejects_unknown_signed_extensions() {
+		let signed_extensions = ["CheckSpecVersion", "CheckAppId"];
+		assert!(dynamic_extra(&signed_extensions, H256::zero(), None).is_err());
+		assert!(DynamicExtra::decode(&mut &[0u8][..]).is_err());
+	}
+
+	#[test]
+	fn dynamic_extra_rejects_fee_asset_without_charge_asset_tx_payment() {
+		let fee_asset = Some(AssetId::from(1984));
+		let signed_extensions = ["CheckNonce", "ChargeTransactionPayment"];
+		assert!(dynamic_extra(&signed_extensions, H256::zero(), fee_asset.clone()).is_err());
+
+		let signed_extensions = ["CheckNonce", "ChargeAssetTxPayment"];
+		let dynamic_extra =
+			dynamic_extra(&signed_extensions, H256::zero(), fee_asset).expect("test case works");
+		assert_eq!(hex::encode(dynamic_extra.encode()), "1ca10f01c0070000");
+	}
+
+	#[test]
+	fn dynamic_extra_includes_the_metadata_hash() {
+		let signed_extensions = ["CheckNonce", "CheckMetadataHash"];
+		let era_info = (Era::Immortal, None);
+		let dynamic_extra = |metadata_hash| {
+			DynamicExtra::new::<KusamaRuntime, _>(
+				&signed_extensions[..],
+				2026,
+				4,
+				7,
+				H256::zero(),
+				era_info,
+				0,
+				None,
+				metadata_hash,
+			)
+		};
+
+		let disabled = dynamic_extra(None).expect("test case works");
+		assert_eq!(hex::encode(disabled.encode()), "1c00");
+		assert_eq!(disabled.additional_signed().expect("test case works").0, [0]);
+
+		let enabled = dynamic_extra(Some([1; 32])).expect("test case works");
+		assert_eq!(hex::encode(enabled.encode()), "1c01");
+		assert_eq!(
+			enabled.additional_signed().expect("test case works").0,
+			[[1].as_ref(), &[1; 32]].concat()
+		);
+
+		let without_check = DynamicExtra::new::<KusamaRuntime, _>(
+			&["CheckNonce"],
+			2026,
+			4,
+			7,
+			H256::zero(),
+			era_info,
+			0,
+			None,
+			Some([1; 32]),
+		);
+		assert!(without_check.is_err());
+	}
+}
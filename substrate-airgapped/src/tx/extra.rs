@@ -1,8 +1,9 @@
+use super::verify::AdditionalSignedData;
 use crate::{
 	frame::{balances::Balances, system::System},
 	runtimes::Runtime,
 };
-use codec::{Decode, Encode};
+use codec::{Decode, Encode, EncodeAsRef, HasCompact, Input, Output};
 use core::{fmt::Debug, marker::PhantomData};
 use sp_runtime::{
 	generic::Era, traits::SignedExtension, transaction_validity::TransactionValidityError,
@@ -11,8 +12,8 @@ use sp_runtime::{
 /// Extra type.
 pub type Extra<R> = <<R as Runtime>::Extra as SignedExtra<R>>::Extra;
 
-/// SignedExtra checks copied from substrate, in order to remove requirement to implement
-/// substrate's `frame_system::Trait`
+// SignedExtra checks copied from substrate, in order to remove requirement to implement
+// substrate's `frame_system::Trait`
 
 /// Ensure the runtime version registered in the transaction is the same as at present.
 ///
@@ -129,6 +130,36 @@ where
 	}
 }
 
+/// Check for transaction mortality. `CheckEra` under the name it has in current Substrate.
+///
+/// # Note
+///
+/// This is modified from the substrate version to allow passing in of the hash of the block the
+/// mortality period starts at (the genesis hash if immortal), which is returned via
+/// `additional_signed()`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Debug)]
+pub struct CheckMortality<T: System>(
+	/// The default structure for the Extra encoding
+	pub (Era, PhantomData<T>),
+	/// Local checkpoint block hash to be used for `AdditionalSigned`
+	#[codec(skip)]
+	pub T::Hash,
+);
+
+impl<T> SignedExtension for CheckMortality<T>
+where
+	T: System + Clone + Debug + Eq + Send + Sync,
+{
+	const IDENTIFIER: &'static str = "CheckMortality";
+	type AccountId = u64;
+	type Call = ();
+	type AdditionalSigned = T::Hash;
+	type Pre = ();
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		Ok(self.1)
+	}
+}
+
 /// Nonce check and increment to give replay protection for transactions.
 #[derive(Encode, Decode, Clone, Eq, PartialEq, Debug)]
 pub struct CheckNonce<T: System>(#[codec(compact)] pub T::Index);
@@ -184,12 +215,200 @@ where
 	}
 }
 
+/// Id of an asset to pay transaction fees in, as SCALE encoded for the runtime's asset id type,
+/// e.g. a `u32` on Statemine and Statemint or an XCM `Location` on chains using the asset
+/// conversion pallet.
+///
+/// As the length of the encoding depends on that type, an `AssetId` on its own can not be decoded
+/// and its `Decode`, which every `SignedExtension` requires, always errors. It is decoded as part
+/// of a `ChargeAssetTxPayment` with the asset id type of the runtime instead, e.g. by
+/// `AssetHubExtra::decode_signed`, or with the type registry of the runtime metadata.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct AssetId(pub Vec<u8>);
+
+impl AssetId {
+	/// Create an `AssetId` from an id of the runtime's asset id type.
+	pub fn new<I: Encode>(id: I) -> Self {
+		AssetId(id.encode())
+	}
+}
+
+impl From<u32> for AssetId {
+	fn from(id: u32) -> Self {
+		AssetId::new(id)
+	}
+}
+
+impl Encode for AssetId {
+	fn size_hint(&self) -> usize {
+		self.0.len()
+	}
+
+	fn encode_to<T: Output>(&self, dest: &mut T) {
+		dest.write(&self.0)
+	}
+}
+
+impl Decode for AssetId {
+	fn decode<I: Input>(_input: &mut I) -> Result<Self, codec::Error> {
+		Err("AssetId can not be decoded without the asset id type of the runtime".into())
+	}
+}
+
+/// Require the transactor pay for themselves, in the native token or the given asset, and maybe
+/// include a tip to gain additional priority in the queue. Used in place of
+/// `ChargeTransactionPayment` by chains with an assets pallet, e.g. Statemine.
+///
+/// The asset id is decoded as the runtime's asset id type `A`, e.g. `u32` on Statemine.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ChargeAssetTxPayment<T: Balances, A = AssetId> {
+	/// Tip, in the native token even if the fees are paid in an asset.
+	pub tip: T::Balance,
+	/// Asset to pay the fees in, the native token if `None`.
+	pub asset_id: Option<AssetId>,
+	/// Asset id type of the runtime.
+	pub asset_id_type: PhantomData<A>,
+}
+
+impl<T: Balances, A> ChargeAssetTxPayment<T, A> {
+	/// Create a `ChargeAssetTxPayment` with the given tip and asset to pay the fees in.
+	pub fn new(tip: T::Balance, asset_id: Option<AssetId>) -> Self {
+		ChargeAssetTxPayment { tip, asset_id, asset_id_type: PhantomData }
+	}
+}
+
+impl<T: Balances, A> Encode for ChargeAssetTxPayment<T, A> {
+	fn encode_to<O: Output>(&self, dest: &mut O) {
+		<<T::Balance as HasCompact>::Type as EncodeAsRef<'_, T::Balance>>::RefType::from(&self.tip)
+			.encode_to(dest);
+		self.asset_id.encode_to(dest);
+	}
+}
+
+impl<T: Balances, A: Encode + Decode> Decode for ChargeAssetTxPayment<T, A> {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let tip = <<T::Balance as HasCompact>::Type>::decode(input)?.into();
+		let asset_id = <Option<A>>::decode(input)?.map(AssetId::new);
+
+		Ok(Self::new(tip, asset_id))
+	}
+}
+
+impl<T, A> SignedExtension for ChargeAssetTxPayment<T, A>
+where
+	T: Balances + Clone + Debug + Eq + Send + Sync,
+	A: Encode + Decode + Clone + Debug + Eq + Send + Sync,
+{
+	const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
+	type AccountId = u64;
+	type Call = ();
+	type AdditionalSigned = ();
+	type Pre = ();
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		Ok(())
+	}
+}
+
+/// Whether a transaction includes the metadata hash of `CheckMetadataHash`.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, Debug)]
+pub enum MetadataHashMode {
+	/// The metadata hash is not checked.
+	Disabled,
+	/// The metadata hash is part of the additional signed data and checked by the runtime.
+	Enabled,
+}
+
+/// Check the metadata hash of RFC-78, the hash of the metadata a signer used to decode the
+/// transaction, so it can trust the description of the transaction it shows.
+///
+/// # Note
+///
+/// This is modified from the substrate version to allow passing in of the metadata hash, which is
+/// returned via `additional_signed()`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Debug)]
+pub struct CheckMetadataHash {
+	/// Whether the metadata hash is checked.
+	pub mode: MetadataHashMode,
+	/// Local metadata hash to be used for `AdditionalSigned`
+	#[codec(skip)]
+	pub metadata_hash: Option<[u8; 32]>,
+}
+
+impl CheckMetadataHash {
+	/// Create a `CheckMetadataHash`, enabled if there is a metadata hash.
+	pub fn new(metadata_hash: Option<[u8; 32]>) -> Self {
+		let mode = if metadata_hash.is_some() {
+			MetadataHashMode::Enabled
+		} else {
+			MetadataHashMode::Disabled
+		};
+
+		CheckMetadataHash { mode, metadata_hash }
+	}
+}
+
+impl SignedExtension for CheckMetadataHash {
+	const IDENTIFIER: &'static str = "CheckMetadataHash";
+	type AccountId = u64;
+	type Call = ();
+	type AdditionalSigned = Option<[u8; 32]>;
+	type Pre = ();
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		match self.mode {
+			MetadataHashMode::Enabled => Ok(self.metadata_hash),
+			MetadataHashMode::Disabled => Ok(None),
+		}
+	}
+}
+
+/// Ensure the sender of a transaction is not the all zero account.
+///
+/// Neither encodes any data into the extrinsic nor adds any additional signed data.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Debug)]
+pub struct CheckNonZeroSender<T: System>(pub PhantomData<T>);
+
+impl<T> SignedExtension for CheckNonZeroSender<T>
+where
+	T: System + Clone + Debug + Eq + Send + Sync,
+{
+	const IDENTIFIER: &'static str = "CheckNonZeroSender";
+	type AccountId = u64;
+	type Call = ();
+	type AdditionalSigned = ();
+	type Pre = ();
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		Ok(())
+	}
+}
+
+/// Validate `attest` calls of Polkadot's claims pallet prior to execution.
+///
+/// Only part of the extra of the Polkadot runtime. It neither encodes any data into the extrinsic
+/// nor adds any additional signed data.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Debug)]
+pub struct PrevalidateAttests<T: System>(pub PhantomData<T>);
+
+impl<T> SignedExtension for PrevalidateAttests<T>
+where
+	T: System + Clone + Debug + Eq + Send + Sync,
+{
+	const IDENTIFIER: &'static str = "PrevalidateAttests";
+	type AccountId = u64;
+	type Call = ();
+	type AdditionalSigned = ();
+	type Pre = ();
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		Ok(())
+	}
+}
+
 /// Trait for implementing transaction extras for a runtime.
 pub trait SignedExtra<T: System + Balances>: SignedExtension {
 	/// The type the extras.
 	type Extra: SignedExtension + Send + Sync;
 
 	/// Creates a new `SignedExtra`.
+	#[allow(clippy::too_many_arguments)]
 	fn new(
 		spec_version: u32,
 		tx_version: u32,
@@ -197,21 +416,74 @@ pub trait SignedExtra<T: System + Balances>: SignedExtension {
 		genesis_hash: T::Hash,
 		era_info: (Era, Option<T::Hash>),
 		tip: T::Balance,
+		fee_asset: Option<AssetId>,
+		metadata_hash: Option<[u8; 32]>,
 	) -> Self;
 
 	/// Returns the transaction extra.
 	fn extra(&self) -> Self::Extra;
+
+	/// Create the `SignedExtra` of a signed extrinsic from its extra and the additional signed data
+	/// it was signed with, which is not part of the extrinsic.
+	fn from_extra(
+		extra: Self::Extra,
+		additional_signed: &AdditionalSignedData<T>,
+	) -> Result<Self, codec::Error>
+	where
+		Self: Sized;
+
+	/// Decode a `SignedExtra` from the data of its extra followed by its additional signed data,
+	/// as they are encoded in a `SignedPayload`.
+	fn decode_signed<I: Input>(input: &mut I) -> Result<Self, codec::Error>
+	where
+		Self: Sized;
+
+	/// The `DefaultExtra` holding the values the `SignedExtra` was created with.
+	fn default_extra(&self) -> &DefaultExtra<T>;
+}
+
+/// The `era_info` of `SignedExtra::new` from the era of a signed extrinsic and the checkpoint block
+/// hash of its additional signed data, which is the genesis hash for immortal transactions.
+fn signed_era_info<T: System>(
+	era: Era,
+	additional_signed: &AdditionalSignedData<T>,
+) -> Result<(Era, Option<T::Hash>), codec::Error> {
+	let genesis_hash = additional_signed.genesis_hash;
+	match (era, additional_signed.checkpoint_hash) {
+		(Era::Immortal, Some(era_hash)) if era_hash != genesis_hash => {
+			Err("Checkpoint hash of an immortal transaction is not the genesis hash".into())
+		}
+		(Era::Immortal, _) => Ok((era, None)),
+		(Era::Mortal(..), era_hash) => Ok((era, Some(era_hash.unwrap_or(genesis_hash)))),
+	}
+}
+
+/// The `metadata_hash` of `SignedExtra::new` from the mode of `CheckMetadataHash` of a signed
+/// extrinsic and the metadata hash of its additional signed data, which is only included if the
+/// mode is enabled.
+fn signed_metadata_hash(
+	CheckMetadataHash { mode, .. }: CheckMetadataHash,
+	metadata_hash: Option<[u8; 32]>,
+) -> Result<Option<[u8; 32]>, codec::Error> {
+	match (mode, metadata_hash) {
+		(MetadataHashMode::Enabled, Some(_)) | (MetadataHashMode::Disabled, None) => {
+			Ok(metadata_hash)
+		}
+		_ => Err("Metadata hash does not match the mode of CheckMetadataHash".into()),
+	}
 }
 
 /// Default `SignedExtra` for substrate runtimes.
 #[derive(Encode, Decode, Clone, Eq, PartialEq, Debug)]
 pub struct DefaultExtra<T: System + Balances> {
-	spec_version: u32,
-	tx_version: u32,
-	nonce: T::Index,
-	genesis_hash: T::Hash,
-	era_info: (Era, Option<T::Hash>),
-	tip: T::Balance,
+	pub(crate) spec_version: u32,
+	pub(crate) tx_version: u32,
+	pub(crate) nonce: T::Index,
+	pub(crate) genesis_hash: T::Hash,
+	pub(crate) era_info: (Era, Option<T::Hash>),
+	pub(crate) tip: T::Balance,
+	pub(crate) fee_asset: Option<AssetId>,
+	pub(crate) metadata_hash: Option<[u8; 32]>,
 }
 
 impl<T: System + Balances + Clone + Debug + Eq + Send + Sync> SignedExtra<T> for DefaultExtra<T> {
@@ -226,6 +498,7 @@ impl<T: System + Balances + Clone + Debug + Eq + Send + Sync> SignedExtra<T> for
 		ChargeTransactionPayment<T>,
 	);
 
+	#[allow(clippy::too_many_arguments)]
 	fn new(
 		spec_version: u32,
 		tx_version: u32,
@@ -233,8 +506,19 @@ impl<T: System + Balances + Clone + Debug + Eq + Send + Sync> SignedExtra<T> for
 		genesis_hash: T::Hash,
 		era_info: (Era, Option<T::Hash>),
 		tip: T::Balance,
+		fee_asset: Option<AssetId>,
+		metadata_hash: Option<[u8; 32]>,
 	) -> Self {
-		DefaultExtra { spec_version, tx_version, nonce, genesis_hash, era_info, tip }
+		DefaultExtra {
+			spec_version,
+			tx_version,
+			nonce,
+			genesis_hash,
+			era_info,
+			tip,
+			fee_asset,
+			metadata_hash,
+		}
 	}
 
 	fn extra(&self) -> Self::Extra {
@@ -249,6 +533,46 @@ impl<T: System + Balances + Clone + Debug + Eq + Send + Sync> SignedExtra<T> for
 			ChargeTransactionPayment(self.tip),
 		)
 	}
+
+	fn from_extra(
+		extra: Self::Extra,
+		additional_signed: &AdditionalSignedData<T>,
+	) -> Result<Self, codec::Error> {
+		let (_, _, _, CheckEra((era, _), _), CheckNonce(nonce), _, ChargeTransactionPayment(tip)) =
+			extra;
+
+		Ok(Self::new(
+			additional_signed.spec_version,
+			additional_signed.tx_version,
+			nonce,
+			additional_signed.genesis_hash,
+			signed_era_info(era, additional_signed)?,
+			tip,
+			None,
+			None,
+		))
+	}
+
+	fn decode_signed<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let extra = Self::Extra::decode(input)?;
+		let (spec_version, tx_version, genesis_hash, checkpoint_hash, (), (), ()) =
+			Decode::decode(input)?;
+
+		Self::from_extra(
+			extra,
+			&AdditionalSignedData {
+				spec_version,
+				tx_version,
+				genesis_hash,
+				checkpoint_hash: Some(checkpoint_hash),
+				metadata_hash: None,
+			},
+		)
+	}
+
+	fn default_extra(&self) -> &DefaultExtra<T> {
+		self
+	}
 }
 
 impl<T: System + Balances + Clone + Debug + Eq + Send + Sync> SignedExtension for DefaultExtra<T> {
@@ -262,3 +586,607 @@ impl<T: System + Balances + Clone + Debug + Eq + Send + Sync> SignedExtension fo
 		self.extra().additional_signed()
 	}
 }
+
+/// `SignedExtra` of current relay chain runtimes: Kusama, Westend and Rococo. Compared to the
+/// `DefaultExtra`, it starts with `CheckNonZeroSender`, has `CheckMortality` in place of
+/// `CheckEra` and ends with `CheckMetadataHash`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Debug)]
+pub struct CurrentExtra<T: System + Balances>(DefaultExtra<T>);
+
+impl<T: System + Balances + Clone + Debug + Eq + Send + Sync> SignedExtra<T> for CurrentExtra<T> {
+	#[allow(clippy::type_complexity)]
+	type Extra = (
+		CheckNonZeroSender<T>,
+		CheckSpecVersion<T>,
+		CheckTxVersion<T>,
+		CheckGenesis<T>,
+		CheckMortality<T>,
+		CheckNonce<T>,
+		CheckWeight<T>,
+		ChargeTransactionPayment<T>,
+		CheckMetadataHash,
+	);
+
+	#[allow(clippy::too_many_arguments)]
+	fn new(
+		spec_version: u32,
+		tx_version: u32,
+		nonce: T::Index,
+		genesis_hash: T::Hash,
+		era_info: (Era, Option<T::Hash>),
+		tip: T::Balance,
+		fee_asset: Option<AssetId>,
+		metadata_hash: Option<[u8; 32]>,
+	) -> Self {
+		CurrentExtra(DefaultExtra::new(
+			spec_version,
+			tx_version,
+			nonce,
+			genesis_hash,
+			era_info,
+			tip,
+			fee_asset,
+			metadata_hash,
+		))
+	}
+
+	fn extra(&self) -> Self::Extra {
+		let (spec_version, tx_version, genesis, CheckEra(era, era_hash), nonce, weight, payment) =
+			self.0.extra();
+		(
+			CheckNonZeroSender(PhantomData),
+			spec_version,
+			tx_version,
+			genesis,
+			CheckMortality(era, era_hash),
+			nonce,
+			weight,
+			payment,
+			CheckMetadataHash::new(self.0.metadata_hash),
+		)
+	}
+
+	fn from_extra(
+		extra: Self::Extra,
+		additional_signed: &AdditionalSignedData<T>,
+	) -> Result<Self, codec::Error> {
+		let (_, _, _, _, CheckMortality((era, _), _), CheckNonce(nonce), _, payment, mode) = extra;
+
+		Ok(Self::new(
+			additional_signed.spec_version,
+			additional_signed.tx_version,
+			nonce,
+			additional_signed.genesis_hash,
+			signed_era_info(era, additional_signed)?,
+			payment.0,
+			None,
+			signed_metadata_hash(mode, additional_signed.metadata_hash)?,
+		))
+	}
+
+	fn decode_signed<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let extra = Self::Extra::decode(input)?;
+		let (
+			(),
+			spec_version,
+			tx_version,
+			genesis_hash,
+			checkpoint_hash,
+			(),
+			(),
+			(),
+			metadata_hash,
+		) = Decode::decode(input)?;
+
+		Self::from_extra(
+			extra,
+			&AdditionalSignedData {
+				spec_version,
+				tx_version,
+				genesis_hash,
+				checkpoint_hash: Some(checkpoint_hash),
+				metadata_hash,
+			},
+		)
+	}
+
+	fn default_extra(&self) -> &DefaultExtra<T> {
+		&self.0
+	}
+}
+
+impl<T: System + Balances + Clone + Debug + Eq + Send + Sync> SignedExtension for CurrentExtra<T> {
+	const IDENTIFIER: &'static str = "CurrentExtra";
+	type AccountId = T::AccountId;
+	type Call = ();
+	type AdditionalSigned = <<Self as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned;
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		self.extra().additional_signed()
+	}
+}
+
+/// `SignedExtra` of the Polkadot runtime, which adds `PrevalidateAttests` to the `CurrentExtra`,
+/// before its `CheckMetadataHash`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Debug)]
+pub struct PolkadotExtra<T: System + Balances>(CurrentExtra<T>);
+
+impl<T: System + Balances + Clone + Debug + Eq + Send + Sync> SignedExtra<T> for PolkadotExtra<T> {
+	#[allow(clippy::type_complexity)]
+	type Extra = (
+		CheckNonZeroSender<T>,
+		CheckSpecVersion<T>,
+		CheckTxVersion<T>,
+		CheckGenesis<T>,
+		CheckMortality<T>,
+		CheckNonce<T>,
+		CheckWeight<T>,
+		ChargeTransactionPayment<T>,
+		PrevalidateAttests<T>,
+		CheckMetadataHash,
+	);
+
+	#[allow(clippy::too_many_arguments)]
+	fn new(
+		spec_version: u32,
+		tx_version: u32,
+		nonce: T::Index,
+		genesis_hash: T::Hash,
+		era_info: (Era, Option<T::Hash>),
+		tip: T::Balance,
+		fee_asset: Option<AssetId>,
+		metadata_hash: Option<[u8; 32]>,
+	) -> Self {
+		PolkadotExtra(CurrentExtra::new(
+			spec_version,
+			tx_version,
+			nonce,
+			genesis_hash,
+			era_info,
+			tip,
+			fee_asset,
+			metadata_hash,
+		))
+	}
+
+	fn extra(&self) -> Self::Extra {
+		let (
+			non_zero_sender,
+			spec_version,
+			tx_version,
+			genesis,
+			mortality,
+			nonce,
+			weight,
+			payment,
+			metadata_hash,
+		) = self.0.extra();
+		(
+			non_zero_sender,
+			spec_version,
+			tx_version,
+			genesis,
+			mortality,
+			nonce,
+			weight,
+			payment,
+			PrevalidateAttests(PhantomData),
+			metadata_hash,
+		)
+	}
+
+	fn from_extra(
+		extra: Self::Extra,
+		additional_signed: &AdditionalSignedData<T>,
+	) -> Result<Self, codec::Error> {
+		let (_, _, _, _, CheckMortality((era, _), _), CheckNonce(nonce), _, payment, _, mode) =
+			extra;
+
+		Ok(Self::new(
+			additional_signed.spec_version,
+			additional_signed.tx_version,
+			nonce,
+			additional_signed.genesis_hash,
+			signed_era_info(era, additional_signed)?,
+			payment.0,
+			None,
+			signed_metadata_hash(mode, additional_signed.metadata_hash)?,
+		))
+	}
+
+	fn decode_signed<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let extra = Self::Extra::decode(input)?;
+		let (
+			(),
+			spec_version,
+			tx_version,
+			genesis_hash,
+			checkpoint_hash,
+			(),
+			(),
+			(),
+			(),
+			metadata_hash,
+		) = Decode::decode(input)?;
+
+		Self::from_extra(
+			extra,
+			&AdditionalSignedData {
+				spec_version,
+				tx_version,
+				genesis_hash,
+				checkpoint_hash: Some(checkpoint_hash),
+				metadata_hash,
+			},
+		)
+	}
+
+	fn default_extra(&self) -> &DefaultExtra<T> {
+		&(self.0).0
+	}
+}
+
+impl<T: System + Balances + Clone + Debug + Eq + Send + Sync> SignedExtension for PolkadotExtra<T> {
+	const IDENTIFIER: &'static str = "PolkadotExtra";
+	type AccountId = T::AccountId;
+	type Call = ();
+	type AdditionalSigned = <<Self as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned;
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		self.extra().additional_signed()
+	}
+}
+
+/// `SignedExtra` of asset hub runtimes, e.g. Statemine's, which have `ChargeAssetTxPayment` in
+/// place of the `ChargeTransactionPayment` of the `CurrentExtra`, so fees can be paid in an asset
+/// of the asset id type `A`, e.g. `u32` on Statemine.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Debug)]
+pub struct AssetHubExtra<T: System + Balances, A>(CurrentExtra<T>, PhantomData<A>);
+
+impl<T, A> SignedExtra<T> for AssetHubExtra<T, A>
+where
+	T: System + Balances + Clone + Debug + Eq + Send + Sync,
+	A: Encode + Decode + Clone + Debug + Eq + Send + Sync,
+{
+	#[allow(clippy::type_complexity)]
+	type Extra = (
+		CheckNonZeroSender<T>,
+		CheckSpecVersion<T>,
+		CheckTxVersion<T>,
+		CheckGenesis<T>,
+		CheckMortality<T>,
+		CheckNonce<T>,
+		CheckWeight<T>,
+		ChargeAssetTxPayment<T, A>,
+		CheckMetadataHash,
+	);
+
+	#[allow(clippy::too_many_arguments)]
+	fn new(
+		spec_version: u32,
+		tx_version: u32,
+		nonce: T::Index,
+		genesis_hash: T::Hash,
+		era_info: (Era, Option<T::Hash>),
+		tip: T::Balance,
+		fee_asset: Option<AssetId>,
+		metadata_hash: Option<[u8; 32]>,
+	) -> Self {
+		AssetHubExtra(
+			CurrentExtra::new(
+				spec_version,
+				tx_version,
+				nonce,
+				genesis_hash,
+				era_info,
+				tip,
+				fee_asset,
+				metadata_hash,
+			),
+			PhantomData,
+		)
+	}
+
+	fn extra(&self) -> Self::Extra {
+		let (
+			non_zero_sender,
+			spec_version,
+			tx_version,
+			genesis,
+			mortality,
+			nonce,
+			weight,
+			payment,
+			metadata_hash,
+		) = self.0.extra();
+		(
+			non_zero_sender,
+			spec_version,
+			tx_version,
+			genesis,
+			mortality,
+			nonce,
+			weight,
+			ChargeAssetTxPayment::new(payment.0, (self.0).0.fee_asset.clone()),
+			metadata_hash,
+		)
+	}
+
+	fn from_extra(
+		extra: Self::Extra,
+		additional_signed: &AdditionalSignedData<T>,
+	) -> Result<Self, codec::Error> {
+		let (_, _, _, _, CheckMortality((era, _), _), CheckNonce(nonce), _, payment, mode) = extra;
+
+		Ok(Self::new(
+			additional_signed.spec_version,
+			additional_signed.tx_version,
+			nonce,
+			additional_signed.genesis_hash,
+			signed_era_info(era, additional_signed)?,
+			payment.tip,
+			payment.asset_id,
+			signed_metadata_hash(mode, additional_signed.metadata_hash)?,
+		))
+	}
+
+	fn decode_signed<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let extra = Self::Extra::decode(input)?;
+		let (
+			(),
+			spec_version,
+			tx_version,
+			genesis_hash,
+			checkpoint_hash,
+			(),
+			(),
+			(),
+			metadata_hash,
+		) = Decode::decode(input)?;
+
+		Self::from_extra(
+			extra,
+			&AdditionalSignedData {
+				spec_version,
+				tx_version,
+				genesis_hash,
+				checkpoint_hash: Some(checkpoint_hash),
+				metadata_hash,
+			},
+		)
+	}
+
+	fn default_extra(&self) -> &DefaultExtra<T> {
+		&(self.0).0
+	}
+}
+
+impl<T, A> SignedExtension for AssetHubExtra<T, A>
+where
+	T: System + Balances + Clone + Debug + Eq + Send + Sync,
+	A: Encode + Decode + Clone + Debug + Eq + Send + Sync,
+{
+	const IDENTIFIER: &'static str = "AssetHubExtra";
+	type AccountId = T::AccountId;
+	type Call = ();
+	type AdditionalSigned = <<Self as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned;
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		self.extra().additional_signed()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{KusamaRuntime, PolkadotRuntime, StatemineRuntime};
+	use sp_core::H256;
+
+	const ERA_NONCE_AND_TIP: &str = "45021ca10f";
+	const ADDITIONAL_SIGNED: &str = concat!(
+		// Spec and transaction version
+		"d6240000",
+		"1a000000",
+		// Kusama genesis hash
+		"b0a8d493285c2df73290dfb7e61f870f17b41801197a149ca93654499ea3dafe",
+		// Checkpoint block hash
+		"0101010101010101010101010101010101010101010101010101010101010101",
+	);
+
+	fn extra<T, E>(fee_asset: Option<AssetId>, metadata_hash: Option<[u8; 32]>) -> E::Extra
+	where
+		T: System<Index = u32, Hash = H256> + Balances<Balance = u128>,
+		E: SignedExtra<T>,
+	{
+		let era_info = (Era::mortal(64, 100), Some(H256::repeat_byte(1)));
+		let genesis_hash = KusamaRuntime::GENESIS_HASH;
+		E::new(9430, 26, 7, genesis_hash, era_info, 1_000, fee_asset, metadata_hash).extra()
+	}
+
+	#[test]
+	fn check_mortality_and_check_non_zero_sender_encode() {
+		let mortality = CheckMortality::<KusamaRuntime>(
+			(Era::mortal(64, 100), PhantomData),
+			H256::repeat_byte(1),
+		);
+		assert_eq!(hex::encode(mortality.encode()), "4502");
+		assert_eq!(mortality.additional_signed(), Ok(H256::repeat_byte(1)));
+		assert_eq!(CheckMortality::<KusamaRuntime>::IDENTIFIER, "CheckMortality");
+
+		let non_zero_sender = CheckNonZeroSender::<KusamaRuntime>(PhantomData);
+		assert!(non_zero_sender.encode().is_empty());
+		assert_eq!(non_zero_sender.additional_signed(), Ok(()));
+		assert_eq!(CheckNonZeroSender::<KusamaRuntime>::IDENTIFIER, "CheckNonZeroSender");
+	}
+
+	#[test]
+	fn current_extra_encodes() {
+		let disabled = extra::<KusamaRuntime, CurrentExtra<KusamaRuntime>>(None, None);
+
+		// Disabled `CheckMetadataHash`
+		assert_eq!(hex::encode(disabled.encode()), [ERA_NONCE_AND_TIP, "00"].concat());
+		assert_eq!(
+			hex::encode(disabled.additional_signed().expect("test case works").encode()),
+			[ADDITIONAL_SIGNED, "00"].concat()
+		);
+		assert_eq!(
+			<CurrentExtra<KusamaRuntime> as SignedExtra<KusamaRuntime>>::Extra::identifier(),
+			[
+				"CheckNonZeroSender",
+				"CheckSpecVersion",
+				"CheckTxVersion",
+				"CheckGenesis",
+				"CheckMortality",
+				"CheckNonce",
+				"CheckWeight",
+				"ChargeTransactionPayment",
+				"CheckMetadataHash",
+			]
+		);
+
+		let enabled = extra::<KusamaRuntime, CurrentExtra<KusamaRuntime>>(None, Some([2; 32]));
+		assert_eq!(hex::encode(enabled.encode()), [ERA_NONCE_AND_TIP, "01"].concat());
+		assert_eq!(
+			hex::encode(enabled.additional_signed().expect("test case works").encode()),
+			[ADDITIONAL_SIGNED, "01", &"02".repeat(32)].concat()
+		);
+	}
+
+	#[test]
+	fn polkadot_extra_encodes() {
+		let extra = extra::<PolkadotRuntime, PolkadotExtra<PolkadotRuntime>>(None, None);
+
+		assert_eq!(hex::encode(extra.encode()), [ERA_NONCE_AND_TIP, "00"].concat());
+		assert_eq!(
+			hex::encode(extra.additional_signed().expect("test case works").encode()),
+			[ADDITIONAL_SIGNED, "00"].concat()
+		);
+		assert_eq!(
+			<PolkadotExtra<PolkadotRuntime> as SignedExtra<PolkadotRuntime>>::Extra::identifier()
+				[8..],
+			["PrevalidateAttests", "CheckMetadataHash"]
+		);
+	}
+
+	#[test]
+	fn charge_asset_tx_payment_encodes() {
+		let native = ChargeAssetTxPayment::<StatemineRuntime, u32>::new(1_000, None);
+		assert_eq!(hex::encode(native.encode()), "a10f00");
+		assert_eq!(
+			ChargeAssetTxPayment::<StatemineRuntime, u32>::decode(&mut &native.encode()[..]),
+			Ok(native)
+		);
+
+		let asset = ChargeAssetTxPayment::<StatemineRuntime, u32>::new(0, Some(1984.into()));
+		assert_eq!(hex::encode(asset.encode()), "0001c0070000");
+		assert_eq!(
+			ChargeAssetTxPayment::<StatemineRuntime, u32>::decode(&mut &asset.encode()[..]),
+			Ok(asset.clone())
+		);
+		assert_eq!(asset.additional_signed(), Ok(()));
+
+		// Without the asset id type of the runtime the asset id can not be decoded
+		assert!(ChargeAssetTxPayment::<StatemineRuntime>::decode(&mut &asset.encode()[..]).is_err());
+	}
+
+	#[test]
+	fn asset_hub_extra_encodes() {
+		let extra = extra::<StatemineRuntime, AssetHubExtra<StatemineRuntime, u32>>(
+			Some(1984.into()),
+			None,
+		);
+
+		assert_eq!(hex::encode(extra.encode()), [ERA_NONCE_AND_TIP, "01c0070000", "00"].concat());
+		assert_eq!(
+			hex::encode(extra.additional_signed().expect("test case works").encode()),
+			[ADDITIONAL_SIGNED, "00"].concat()
+		);
+		assert_eq!(
+			<AssetHubExtra<StatemineRuntime, u32> as SignedExtra<StatemineRuntime>>::Extra::identifier()
+				[7..],
+			["ChargeAssetTxPayment", "CheckMetadataHash"]
+		);
+	}
+
+	#[test]
+	fn check_metadata_hash_encodes() {
+		let disabled = CheckMetadataHash::new(None);
+		assert_eq!(hex::encode(disabled.encode()), "00");
+		assert_eq!(
+			hex::encode(disabled.additional_signed().expect("test case works").encode()),
+			"00"
+		);
+
+		let enabled = CheckMetadataHash::new(Some([1; 32]));
+		assert_eq!(hex::encode(enabled.encode()), "01");
+		assert_eq!(
+			hex::encode(enabled.additional_signed().expect("test case works").encode()),
+			["01", &"01".repeat(32)].concat()
+		);
+		assert_eq!(
+			CheckMetadataHash::decode(&mut &[1u8][..]).map(|check| check.mode),
+			Ok(MetadataHashMode::Enabled)
+		);
+	}
+
+	fn assert_decode_signed<T, E>(fee_asset: Option<AssetId>, metadata_hash: Option<[u8; 32]>)
+	where
+		T: System<Index = u32, Hash = H256> + Balances<Balance = u128> + Debug + Eq,
+		E: SignedExtra<T>,
+	{
+		let era_info = (Era::mortal(64, 100), Some(H256::repeat_byte(1)));
+		let genesis_hash = KusamaRuntime::GENESIS_HASH;
+		let signed_extra =
+			E::new(9430, 26, 7, genesis_hash, era_info, 1_000, fee_asset, metadata_hash);
+		let extra = signed_extra.extra();
+		let bytes =
+			[extra.encode(), extra.additional_signed().expect("test case works").encode()].concat();
+
+		let decoded = E::decode_signed(&mut &bytes[..]).expect("test case works");
+		assert_eq!(decoded.default_extra(), signed_extra.default_extra());
+	}
+
+	#[test]
+	fn decode_signed_works() {
+		assert_decode_signed::<KusamaRuntime, DefaultExtra<KusamaRuntime>>(None, None);
+		for metadata_hash in [None, Some([2; 32])].iter().copied() {
+			assert_decode_signed::<KusamaRuntime, CurrentExtra<KusamaRuntime>>(None, metadata_hash);
+			assert_decode_signed::<PolkadotRuntime, PolkadotExtra<PolkadotRuntime>>(
+				None,
+				metadata_hash,
+			);
+			for fee_asset in [None, Some(AssetId::from(1984))].iter().cloned() {
+				assert_decode_signed::<StatemineRuntime, AssetHubExtra<StatemineRuntime, u32>>(
+					fee_asset,
+					metadata_hash,
+				);
+			}
+		}
+
+		// Immortal transactions use the genesis hash as checkpoint block hash
+		let bytes =
+			hex::decode(["00", &ERA_NONCE_AND_TIP[4..], "00", ADDITIONAL_SIGNED, "00"].concat())
+				.expect("test case works");
+		assert!(CurrentExtra::<KusamaRuntime>::decode_signed(&mut &bytes[..]).is_err());
+
+		// Metadata hash of a disabled `CheckMetadataHash`, and none of an enabled one
+		let bytes = hex::decode(
+			[ERA_NONCE_AND_TIP, "00", ADDITIONAL_SIGNED, "01", &"02".repeat(32)].concat(),
+		)
+		.expect("test case works");
+		assert!(CurrentExtra::<KusamaRuntime>::decode_signed(&mut &bytes[..]).is_err());
+		let bytes = hex::decode([ERA_NONCE_AND_TIP, "01", ADDITIONAL_SIGNED, "00"].concat())
+			.expect("test case works");
+		assert!(CurrentExtra::<KusamaRuntime>::decode_signed(&mut &bytes[..]).is_err());
+	}
+
+	#[test]
+	fn asset_id_is_encode_only() {
+		let asset_id = AssetId::from(1984);
+		assert_eq!(asset_id.encode(), 1984u32.encode());
+		assert!(AssetId::decode(&mut &asset_id.encode()[..]).is_err());
+	}
+}
@@ -1,4 +1,4 @@
-use codec::{Decode, Encode, Input};
+use codec::{Decode, Encode, Input, Output};
 use core::fmt::Debug;
 
 /// Call arguments with the call and module index. The indexes are needed encoding.
@@ -66,15 +66,40 @@ impl<C: Encode + Decode + Clone> Encode for GenericCall<C> {
 }
 
 impl<C: Encode + Decode + Clone> Decode for GenericCall<C> {
-	fn decode<I: Input>(value: &mut I) -> Result<Self, codec::Error> {
-		let value_len = value.remaining_len()?.ok_or("Codec Error: No length")?;
-		let mut buf = vec![0; value_len];
-		value.read(&mut buf[..])?;
-
-		Ok(GenericCall {
-			call_index: CallIndex::new(buf[0], buf[1]),
-			args: C::decode(&mut &buf[2..]).unwrap(),
-		})
+	/// Decode the call index and then the arguments, consuming only the bytes of the call, so a
+	/// call can be decoded from the middle of e.g. an `UncheckedExtrinsic`.
+	fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let call_index = CallIndex::decode(input)?;
+		let args = C::decode(input)?;
+
+		Ok(GenericCall { call_index, args })
+	}
+}
+
+/// Call arguments that are already SCALE encoded, e.g. by a tool that constructs calls dynamically.
+///
+/// Decoding consumes all of the remaining input, so this should only be decoded from the exact
+/// bytes of the arguments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RawArgs(pub Vec<u8>);
+
+impl Encode for RawArgs {
+	fn size_hint(&self) -> usize {
+		self.0.len()
+	}
+
+	fn encode_to<T: Output>(&self, dest: &mut T) {
+		dest.write(&self.0)
+	}
+}
+
+impl Decode for RawArgs {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let len = input.remaining_len()?.ok_or("Codec Error: No length")?;
+		let mut args = vec![0; len];
+		input.read(&mut args[..])?;
+
+		Ok(RawArgs(args))
 	}
 }
 
@@ -82,6 +107,7 @@ impl<C: Encode + Decode + Clone> Decode for GenericCall<C> {
 mod test {
 	use super::*;
 	use crate::{balances::Transfer, CallIndex, KusamaRuntime};
+	use codec::DecodeAll;
 	use sp_keyring::AccountKeyring;
 
 	type TransferType = Transfer<KusamaRuntime>;
@@ -89,8 +115,8 @@ mod test {
 
 	#[test]
 	fn generic_call_encode_decode() {
-		let bob_addr = AccountKeyring::Bob.to_account_id().into();
-		let transfer_args: TransferType = Transfer { to: bob_addr, amount: 12 };
+		let bob_addr = AccountKeyring::Bob.to_account_id();
+		let transfer_args: TransferType = Transfer { to: bob_addr.into(), amount: 12 };
 		let transfer = GenericCall::new(CallIndex::new(5, 0), transfer_args);
 
 		// Independent parts of the call encode as expected
@@ -98,19 +124,47 @@ mod test {
 		assert_eq!(
 			transfer.args.encode(),
 			[
-				142, 175, 4, 21, 22, 135, 115, 99, 38, 201, 254, 161, 126, 37, 252, 82, 135, 97,
+				0, 142, 175, 4, 21, 22, 135, 115, 99, 38, 201, 254, 161, 126, 37, 252, 82, 135, 97,
 				54, 147, 201, 18, 144, 156, 178, 38, 170, 71, 148, 242, 106, 72, 48,
 			]
 		);
 
 		// The call itself encodes and decodes as expected
 		let mut call_encoded_expected: &[u8] = &[
-			5, 0, 142, 175, 4, 21, 22, 135, 115, 99, 38, 201, 254, 161, 126, 37, 252, 82, 135, 97,
-			54, 147, 201, 18, 144, 156, 178, 38, 170, 71, 148, 242, 106, 72, 48,
+			5, 0, 0, 142, 175, 4, 21, 22, 135, 115, 99, 38, 201, 254, 161, 126, 37, 252, 82, 135,
+			97, 54, 147, 201, 18, 144, 156, 178, 38, 170, 71, 148, 242, 106, 72, 48,
 		];
 		assert_eq!(transfer.encode(), call_encoded_expected);
 		let decoded_call =
 			TransferCall::decode(&mut call_encoded_expected).expect("decodes from a encoded call");
 		assert_eq!(decoded_call, transfer);
 	}
+
+	#[test]
+	fn generic_call_decode_rejects_truncated_input() {
+		assert!(TransferCall::decode(&mut &[][..]).is_err());
+		assert!(TransferCall::decode(&mut &[5][..]).is_err());
+		assert!(TransferCall::decode(&mut &[5, 0][..]).is_err());
+		assert!(TransferCall::decode(&mut &[5, 0, 0, 142, 175][..]).is_err());
+		// Unknown `MultiAddress` variant
+		assert!(TransferCall::decode(&mut &[5, 0, 9][..]).is_err());
+	}
+
+	#[test]
+	fn generic_call_decode_leaves_trailing_input() {
+		let bob_addr = AccountKeyring::Bob.to_account_id();
+		let transfer =
+			GenericCall::new(CallIndex::new(5, 0), Transfer { to: bob_addr.into(), amount: 12 });
+		let mut encoded = transfer.encode();
+		encoded.extend(&[1, 2, 3]);
+
+		let mut input = &encoded[..];
+		assert_eq!(TransferCall::decode(&mut input), Ok(transfer));
+		assert_eq!(input, [1, 2, 3]);
+		assert!(TransferCall::decode_all(&encoded).is_err());
+
+		let raw = GenericCall::<RawArgs>::decode(&mut &encoded[..]).expect("test case works");
+		assert_eq!(raw.call_index(), &CallIndex::new(5, 0));
+		assert_eq!(raw.args().0, encoded[2..]);
+	}
 }
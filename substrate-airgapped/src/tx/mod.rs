@@ -1,21 +1,31 @@
+mod dynamic_extra;
 pub(crate) mod extra;
 mod generic_call;
 mod mortality;
+mod verify;
 
 pub use self::{
-	generic_call::{CallIndex, GenericCall},
+	dynamic_extra::{DynamicExtra, RawAdditionalSigned, SupportedExtension},
+	extra::AssetId,
+	generic_call::{CallIndex, GenericCall, RawArgs},
 	mortality::{MortalConfig, Mortality},
+	verify::{
+		check_extrinsic, verify_extrinsic, AdditionalSignedData, ExtrinsicChecks, ExtrinsicReport,
+	},
 };
 
 use self::extra::{Extra, SignedExtra};
 use crate::{
+	chain::check_genesis_hash,
 	frame::{balances::Balances, system::System},
 	runtimes::Runtime,
+	signer::{AsyncSigner, Signer},
 	Error,
 };
 use codec::{Decode, Encode};
-use sp_core::Pair;
-use sp_runtime::generic::Era;
+use core::fmt::Debug;
+use sp_core::{hashing::blake2_256, Pair};
+use sp_runtime::{generic::Era, traits::SignedExtension};
 
 /// Local `UncheckedExtrinsic` convenience type. This is a transaction.
 pub type UncheckedExtrinsic<C, R> = sp_runtime::generic::UncheckedExtrinsic<
@@ -28,6 +38,42 @@ pub type UncheckedExtrinsic<C, R> = sp_runtime::generic::UncheckedExtrinsic<
 /// Local `SignedPayload` convenience type. This is the payload that gets signed.
 pub type SignedPayload<C, R> = sp_runtime::generic::SignedPayload<GenericCall<C>, Extra<R>>;
 
+/// `UncheckedExtrinsic` with `DynamicExtra` instead of the `Extra` of the runtime.
+pub type DynamicUncheckedExtrinsic<C, R> = sp_runtime::generic::UncheckedExtrinsic<
+	<R as System>::Address,
+	GenericCall<C>,
+	<R as Runtime>::Signature,
+	DynamicExtra,
+>;
+
+/// `SignedPayload` with `DynamicExtra` instead of the `Extra` of the runtime.
+pub type DynamicSignedPayload<C> = sp_runtime::generic::SignedPayload<GenericCall<C>, DynamicExtra>;
+
+/// Length above which the encoded `SignedPayload` is hashed with `blake2_256` before signing.
+pub const MAX_UNHASHED_PAYLOAD_LEN: usize = 256;
+
+/// The bytes to sign for a transaction, as given to hardware and QR signers.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SigningBytes {
+	/// The encoded `SignedPayload`, or its `blake2_256` hash if it is longer than
+	/// `MAX_UNHASHED_PAYLOAD_LEN` bytes.
+	pub bytes: Vec<u8>,
+	/// Whether `bytes` is the hash of the encoded `SignedPayload`.
+	pub hashed: bool,
+}
+
+impl SigningBytes {
+	/// Create the bytes to sign from the encoding of a `SignedPayload`, hashing it if it is longer
+	/// than `MAX_UNHASHED_PAYLOAD_LEN` bytes.
+	pub fn new(encoded_payload: Vec<u8>) -> Self {
+		if encoded_payload.len() > MAX_UNHASHED_PAYLOAD_LEN {
+			Self { bytes: blake2_256(&encoded_payload).to_vec(), hashed: true }
+		} else {
+			Self { bytes: encoded_payload, hashed: false }
+		}
+	}
+}
+
 /// Configuration options for a Tx
 #[derive(Clone, PartialEq, Debug)]
 pub struct TxConfig<C: Encode + Decode + Clone, R: System + Balances + Runtime> {
@@ -47,6 +93,13 @@ pub struct TxConfig<C: Encode + Decode + Clone, R: System + Balances + Runtime>
 	pub mortality: Mortality<R>,
 	/// Tip, used for transaction priority.
 	pub tip: R::Balance,
+	/// Asset to pay the transaction fees in, instead of the native token. Requires the runtime to
+	/// have the `ChargeAssetTxPayment` signed extension.
+	pub fee_asset: Option<AssetId>,
+	/// RFC-78 hash of the metadata the signer decodes the transaction with, to include in the
+	/// additional signed data. Requires the runtime to have the `CheckMetadataHash` signed
+	/// extension, which is disabled if this is `None`.
+	pub metadata_hash: Option<[u8; 32]>,
 }
 
 /// Transaction builder with all the components to create a signing payload.
@@ -68,6 +121,10 @@ pub struct Tx<C: Encode + Decode + Clone, R: System + Balances + Runtime> {
 	mortality: Mortality<R>,
 	/// Tip, used for transaction priority.
 	tip: R::Balance,
+	/// Asset to pay the transaction fees in, instead of the native token.
+	fee_asset: Option<AssetId>,
+	/// RFC-78 hash of the metadata, checked by the runtime if set.
+	metadata_hash: Option<[u8; 32]>,
 }
 
 /// Create a tx from the senders address, a `SignedPayload` and the signature.
@@ -86,9 +143,13 @@ where
 }
 
 impl<C: Encode + Decode + Clone, R: System + Balances + Runtime> Tx<C, R> {
-	/// Create a transaction builder from TxConfig
-	pub fn new(config: TxConfig<C, R>) -> Self {
-		Tx {
+	/// Create a transaction builder from TxConfig.
+	///
+	/// Errors if the runtime is for a known `Chain` and the genesis hash is not the chain's.
+	pub fn new(config: TxConfig<C, R>) -> Result<Self, Error> {
+		check_genesis_hash::<R>(&config.genesis_hash)?;
+
+		Ok(Tx {
 			call: config.call,
 			address: config.address,
 			nonce: config.nonce,
@@ -97,7 +158,54 @@ impl<C: Encode + Decode + Clone, R: System + Balances + Runtime> Tx<C, R> {
 			genesis_hash: config.genesis_hash,
 			mortality: config.mortality,
 			tip: config.tip,
+			fee_asset: config.fee_asset,
+			metadata_hash: config.metadata_hash,
+		})
+	}
+
+	/// Parse the bytes of a SCALE encoded `SignedPayload` with the `Extra` of the runtime back into
+	/// a `Tx`, so a signer that only receives the bytes can check every field before signing.
+	///
+	/// The signer's address is not part of the payload, so it is given separately. The arguments
+	/// `C` must decode exactly the bytes of the call arguments, which `RawArgs` does not. The
+	/// checkpoint block number of a mortal transaction is only known modulo its period, which
+	/// encodes the same.
+	///
+	/// Errors if the bytes are not such a payload, e.g. for a payload over 256 bytes that is
+	/// hashed before signing, or if the runtime is for a known `Chain` and the genesis hash is not
+	/// the chain's.
+	pub fn from_signed_payload_bytes(bytes: &[u8], address: R::Address) -> Result<Self, Error> {
+		let input = &mut &bytes[..];
+		let call = GenericCall::<C>::decode(input)?;
+		let extra = R::Extra::decode_signed(input)?;
+		if !input.is_empty() {
+			return Err("Signed payload has trailing bytes".into());
 		}
+
+		let extra = extra.default_extra();
+		let mortality = match extra.era_info {
+			(Era::Mortal(period, phase), Some(checkpoint_block_hash)) => {
+				Mortality::Mortal(MortalConfig {
+					period,
+					checkpoint_block_hash,
+					checkpoint_block_number: phase,
+				})
+			}
+			_ => Mortality::Immortal,
+		};
+
+		Tx::new(TxConfig {
+			call,
+			address,
+			nonce: extra.nonce,
+			tx_version: extra.tx_version,
+			spec_version: extra.spec_version,
+			genesis_hash: extra.genesis_hash,
+			mortality,
+			tip: extra.tip,
+			fee_asset: extra.fee_asset.clone(),
+			metadata_hash: extra.metadata_hash,
+		})
 	}
 
 	/// Transaction's call, including arguments and call index.
@@ -141,32 +249,136 @@ impl<C: Encode + Decode + Clone, R: System + Balances + Runtime> Tx<C, R> {
 		&self.tip
 	}
 
-	fn extra(&self) -> <R as Runtime>::Extra {
-		let era_info = match &self.mortality {
+	/// Asset to pay the transaction fees in, `None` for the native token.
+	pub fn fee_asset(&self) -> Option<&AssetId> {
+		self.fee_asset.as_ref()
+	}
+
+	/// RFC-78 metadata hash included in the additional signed data, `None` if not checked.
+	pub fn metadata_hash(&self) -> Option<&[u8; 32]> {
+		self.metadata_hash.as_ref()
+	}
+
+	fn era_info(&self) -> (Era, Option<R::Hash>) {
+		match &self.mortality {
 			Mortality::Mortal(config) => (
 				Era::mortal(config.period, config.checkpoint_block_number),
 				Some(config.checkpoint_block_hash),
 			),
 			Mortality::Immortal => (Era::immortal(), None),
-		};
+		}
+	}
 
+	fn extra(&self) -> <R as Runtime>::Extra {
 		R::Extra::new(
 			self.spec_version,
 			self.tx_version,
 			self.nonce,
 			self.genesis_hash,
-			era_info,
+			self.era_info(),
 			self.tip,
+			self.fee_asset.clone(),
+			self.metadata_hash,
 		)
 	}
 
+	/// Create a `DynamicExtra` for the given signed extensions, e.g. the `signed_extensions` of
+	/// the runtime's metadata, instead of using the `Extra` of the runtime.
+	pub fn dynamic_extra<S: AsRef<str>>(
+		&self,
+		signed_extensions: &[S],
+	) -> Result<DynamicExtra, Error>
+	where
+		R: Clone + Debug + Eq,
+	{
+		DynamicExtra::new::<R, S>(
+			signed_extensions,
+			self.spec_version,
+			self.tx_version,
+			self.nonce,
+			self.genesis_hash,
+			self.era_info(),
+			self.tip,
+			self.fee_asset.clone(),
+			self.metadata_hash,
+		)
+	}
+
+	/// Create a `DynamicSignedPayload`, the payload to sign, with a `DynamicExtra` for the given
+	/// signed extensions.
+	pub fn dynamic_signed_payload<S: AsRef<str>>(
+		&self,
+		signed_extensions: &[S],
+	) -> Result<DynamicSignedPayload<C>, Error>
+	where
+		R: Clone + Debug + Eq,
+	{
+		let extra = self.dynamic_extra(signed_extensions)?;
+
+		DynamicSignedPayload::new(self.call.clone(), extra).map_err(Into::into)
+	}
+
+	/// Create a signed `DynamicUncheckedExtrinsic` with a `DynamicExtra` for the given signed
+	/// extensions, using the given keyring pair to sign.
+	pub fn dynamic_signed_tx_from_pair<P, S>(
+		&self,
+		signed_extensions: &[S],
+		pair: P,
+	) -> Result<DynamicUncheckedExtrinsic<C, R>, Error>
+	where
+		P: Pair,
+		S: AsRef<str>,
+		R: Clone + Debug + Eq,
+		<R as Runtime>::Signature: From<<P as sp_core::Pair>::Signature>,
+	{
+		let payload = self.dynamic_signed_payload(signed_extensions)?;
+		let signature = payload.using_encoded(|payload| pair.sign(payload));
+		let (call, extra, _) = payload.deconstruct();
+
+		Ok(DynamicUncheckedExtrinsic::<C, R>::new_signed(
+			call,
+			self.address.clone(),
+			signature.into(),
+			extra,
+		))
+	}
+
 	/// Create a `SignedPayload`, the payload to sign.
+	///
+	/// Errors if a fee asset is set but the runtime does not have `ChargeAssetTxPayment`, or if a
+	/// metadata hash is set but the runtime does not have `CheckMetadataHash`.
 	pub fn signed_payload(&self) -> Result<SignedPayload<C, R>, Error> {
+		let identifier = Extra::<R>::identifier();
+		if self.fee_asset.is_some() && !identifier.contains(&"ChargeAssetTxPayment") {
+			return Err("Fee asset requires the ChargeAssetTxPayment signed extension".into());
+		}
+		if self.metadata_hash.is_some() && !identifier.contains(&"CheckMetadataHash") {
+			return Err("Metadata hash requires the CheckMetadataHash signed extension".into());
+		}
 		let extra = self.extra();
 
 		SignedPayload::<C, R>::new(self.call.clone(), extra.extra()).map_err(Into::into)
 	}
 
+	/// The bytes to sign, i.e. the encoded `SignedPayload`, hashed if it is longer than
+	/// `MAX_UNHASHED_PAYLOAD_LEN` bytes, for signers which only receive bytes.
+	pub fn signing_bytes(&self) -> Result<SigningBytes, Error> {
+		let (call, extra, additional_signed) = self.signed_payload()?.deconstruct();
+
+		Ok(SigningBytes::new((call, extra, additional_signed).encode()))
+	}
+
+	/// Create a signed `UncheckedExtrinsic` from a signature of the transaction's `signing_bytes`,
+	/// made by the transaction's address.
+	pub fn attach_signature(
+		&self,
+		signature: R::Signature,
+	) -> Result<UncheckedExtrinsic<C, R>, Error> {
+		let payload = self.signed_payload()?;
+
+		Ok(tx_from_parts::<C, R>(self.address.clone(), signature, payload))
+	}
+
 	/// Create a signed `UncheckedExtrinsic` (AKA transaction) using the given keyring pair to sign.
 	pub fn signed_tx_from_pair<P>(&self, pair: P) -> Result<UncheckedExtrinsic<C, R>, Error>
 	where
@@ -179,59 +391,256 @@ impl<C: Encode + Decode + Clone, R: System + Balances + Runtime> Tx<C, R> {
 
 		Ok(tx)
 	}
+
+	/// Create a signed `UncheckedExtrinsic` using the given signer, e.g. a hardware security
+	/// module or an offline device.
+	///
+	/// Errors if the signer signs for another address than the transaction's.
+	pub fn signed_tx<S: Signer<R>>(&self, signer: &S) -> Result<UncheckedExtrinsic<C, R>, Error> {
+		let (address, signature) = signer.sign(&self.signing_bytes()?.bytes)?;
+		if address != self.address {
+			return Err("Signer signed for another address than the transaction's".into());
+		}
+
+		self.attach_signature(signature)
+	}
+
+	/// Create a signed `UncheckedExtrinsic` using the given signer, without blocking while the
+	/// signer waits for a signature.
+	///
+	/// Errors if the signer signs for another address than the transaction's.
+	pub async fn signed_tx_async<S: AsyncSigner<R>>(
+		&self,
+		signer: &S,
+	) -> Result<UncheckedExtrinsic<C, R>, Error> {
+		let signing_bytes = self.signing_bytes()?;
+		let (address, signature) = signer.sign_async(&signing_bytes.bytes).await?;
+		if address != self.address {
+			return Err("Signer signed for another address than the transaction's".into());
+		}
+
+		self.attach_signature(signature)
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::{balances::Transfer, CallIndex, KusamaRuntime};
+	use crate::{balances::Transfer, CallIndex, KusamaRuntime, StatemineRuntime};
 	use sp_keyring::AccountKeyring;
 
 	type TransferType = Transfer<KusamaRuntime>;
 
+	fn tx_config_with_call(call: GenericCall<RawArgs>) -> TxConfig<RawArgs, KusamaRuntime> {
+		TxConfig {
+			call,
+			address: AccountKeyring::Alice.to_account_id().into(),
+			nonce: 0,
+			tx_version: 4,
+			spec_version: 26,
+			genesis_hash: KusamaRuntime::GENESIS_HASH,
+			mortality: Mortality::Immortal,
+			tip: 0,
+			fee_asset: None,
+			metadata_hash: None,
+		}
+	}
+
 	/// Construct an instance of Tx for use in tests.
 	fn test_tx_instance() -> Tx<TransferType, KusamaRuntime> {
-		let bob_addr = AccountKeyring::Bob.to_account_id().into();
-		let alice_addr = AccountKeyring::Alice.to_account_id().into();
+		let bob_addr = AccountKeyring::Bob.to_account_id();
+		let alice_addr = AccountKeyring::Alice.to_account_id();
 
-		let transfer_args: TransferType = Transfer { to: bob_addr, amount: 12 };
+		let transfer_args: TransferType = Transfer { to: bob_addr.into(), amount: 12 };
 		let transfer_call = GenericCall::new(CallIndex::new(5, 0), transfer_args);
-		let genesis_hash = [
-			221, 185, 147, 77, 30, 241, 157, 155, 28, 177, 225, 8, 87, 182, 228, 162, 79, 230, 196,
-			149, 215, 168, 99, 34, 136, 35, 92, 20, 18, 83, 139, 132,
-		];
-		let genesis_hash = sp_core::H256::from_slice(&genesis_hash[..]);
 		let tx_config = TxConfig {
 			call: transfer_call,
-			address: alice_addr,
+			address: alice_addr.into(),
 			nonce: 0,
 			tx_version: 4,
 			spec_version: 26,
-			genesis_hash,
+			genesis_hash: KusamaRuntime::GENESIS_HASH,
 			mortality: Mortality::Immortal,
 			tip: 0,
+			fee_asset: None,
+			metadata_hash: None,
 		};
-		let tx: Tx<TransferType, KusamaRuntime> = Tx::new(tx_config);
+		let tx: Tx<TransferType, KusamaRuntime> = Tx::new(tx_config).expect("test case works");
 
 		tx
 	}
 
+	#[test]
+	fn tx_rejects_genesis_hash_of_other_chain() {
+		let mut config = TxConfig::<TransferType, KusamaRuntime> {
+			call: test_tx_instance().call().clone(),
+			address: AccountKeyring::Alice.to_account_id().into(),
+			nonce: 0,
+			tx_version: 4,
+			spec_version: 26,
+			genesis_hash: crate::PolkadotRuntime::GENESIS_HASH,
+			mortality: Mortality::Immortal,
+			tip: 0,
+			fee_asset: None,
+			metadata_hash: None,
+		};
+		assert!(Tx::new(config.clone()).is_err());
+
+		config.genesis_hash = KusamaRuntime::GENESIS_HASH;
+		assert!(Tx::new(config).is_ok());
+	}
+
+	#[test]
+	fn tx_rejects_fee_asset_without_charge_asset_tx_payment() {
+		let mut tx = test_tx_instance();
+		tx.fee_asset = Some(AssetId::from(1984));
+
+		assert!(tx.signed_payload().is_err());
+		assert!(tx.dynamic_signed_payload(&crate::Chain::Kusama.signed_extensions()).is_err());
+		assert!(tx.dynamic_signed_payload(&crate::Chain::Statemine.signed_extensions()).is_ok());
+	}
+
+	#[test]
+	fn tx_includes_the_metadata_hash() {
+		let mut tx = test_tx_instance();
+		tx.metadata_hash = Some([1; 32]);
+		let signed_extensions = crate::Chain::Kusama.signed_extensions();
+
+		let payload = tx.dynamic_signed_payload(&signed_extensions).expect("test case works");
+		let (_, extra, additional_signed) = payload.deconstruct();
+		assert_eq!(extra.encode().last(), Some(&1));
+		assert_eq!(
+			additional_signed.0[additional_signed.0.len() - 33..],
+			[[1].as_ref(), &[1; 32]].concat()[..]
+		);
+		// The `Extra` of the runtime has the same signed extensions
+		assert_eq!(
+			tx.signed_payload().expect("test case works").encode(),
+			tx.dynamic_signed_payload(&signed_extensions).expect("test case works").encode()
+		);
+
+		let without_metadata_hash = &signed_extensions[..signed_extensions.len() - 1];
+		assert!(tx.dynamic_signed_payload(without_metadata_hash).is_err());
+	}
+
+	#[test]
+	fn tx_from_signed_payload_bytes_works() {
+		let mut tx = test_tx_instance();
+		tx.nonce = 7;
+		tx.tip = 1_000;
+		let payload = tx.signed_payload().expect("test case works").encode();
+		let address = tx.address().clone();
+		assert_eq!(Tx::from_signed_payload_bytes(&payload, address.clone()), Ok(tx.clone()));
+
+		let mut with_metadata_hash = tx.clone();
+		with_metadata_hash.metadata_hash = Some([2; 32]);
+		let payload = with_metadata_hash.signed_payload().expect("test case works").encode();
+		assert_eq!(
+			Tx::from_signed_payload_bytes(&payload, address.clone()),
+			Ok(with_metadata_hash)
+		);
+
+		tx.mortality = Mortality::Mortal(MortalConfig {
+			period: 64,
+			checkpoint_block_hash: sp_core::H256::repeat_byte(1),
+			checkpoint_block_number: 1_000_036,
+		});
+		let payload = tx.signed_payload().expect("test case works").encode();
+		let decoded =
+			Tx::<TransferType, KusamaRuntime>::from_signed_payload_bytes(&payload, address.clone())
+				.expect("test case works");
+		assert_eq!(decoded.signed_payload().expect("test case works").encode(), payload);
+		match decoded.mortality() {
+			Mortality::Mortal(config) => {
+				assert_eq!((config.period, config.checkpoint_block_number), (64, 1_000_036 % 64))
+			}
+			Mortality::Immortal => panic!("expected a mortal transaction"),
+		}
+
+		let mut trailing = payload.clone();
+		trailing.push(0);
+		assert!(Tx::<TransferType, KusamaRuntime>::from_signed_payload_bytes(
+			&trailing,
+			address.clone()
+		)
+		.is_err());
+		assert!(Tx::<TransferType, KusamaRuntime>::from_signed_payload_bytes(
+			&payload[..payload.len() - 1],
+			address.clone()
+		)
+		.is_err());
+		// Payload for Polkadot, with its genesis hash
+		assert!(Tx::<TransferType, crate::PolkadotRuntime>::from_signed_payload_bytes(
+			&payload, address
+		)
+		.is_err());
+	}
+
+	#[test]
+	fn tx_from_signed_payload_bytes_works_with_a_fee_asset() {
+		let call = GenericCall::new(
+			CallIndex::new(10, 0),
+			Transfer::<StatemineRuntime> {
+				to: AccountKeyring::Bob.to_account_id().into(),
+				amount: 12,
+			},
+		);
+		let tx: Tx<Transfer<StatemineRuntime>, StatemineRuntime> = Tx::new(TxConfig {
+			call,
+			address: AccountKeyring::Alice.to_account_id().into(),
+			nonce: 7,
+			tx_version: 10,
+			spec_version: 1_000_000,
+			genesis_hash: StatemineRuntime::GENESIS_HASH,
+			mortality: Mortality::Immortal,
+			tip: 1_000,
+			fee_asset: Some(AssetId::from(1984)),
+			metadata_hash: None,
+		})
+		.expect("test case works");
+		let payload = tx.signed_payload().expect("test case works").encode();
+
+		let decoded = Tx::from_signed_payload_bytes(&payload, tx.address().clone());
+		assert_eq!(decoded, Ok(tx));
+	}
+
 	#[test]
 	fn tx_correctly_constructs_encoded_signed_payload() {
 		let tx = test_tx_instance();
 
 		let signed_payload_encoded_expected = [
-			5, 0, 142, 175, 4, 21, 22, 135, 115, 99, 38, 201, 254, 161, 126, 37, 252, 82, 135, 97,
-			54, 147, 201, 18, 144, 156, 178, 38, 170, 71, 148, 242, 106, 72, 48, 0, 0, 0, 26, 0, 0,
-			0, 4, 0, 0, 0, 221, 185, 147, 77, 30, 241, 157, 155, 28, 177, 225, 8, 87, 182, 228,
-			162, 79, 230, 196, 149, 215, 168, 99, 34, 136, 35, 92, 20, 18, 83, 139, 132, 221, 185,
-			147, 77, 30, 241, 157, 155, 28, 177, 225, 8, 87, 182, 228, 162, 79, 230, 196, 149, 215,
-			168, 99, 34, 136, 35, 92, 20, 18, 83, 139, 132,
+			5, 0, 0, 142, 175, 4, 21, 22, 135, 115, 99, 38, 201, 254, 161, 126, 37, 252, 82, 135,
+			97, 54, 147, 201, 18, 144, 156, 178, 38, 170, 71, 148, 242, 106, 72, 48, 0, 0, 0, 0,
+			26, 0, 0, 0, 4, 0, 0, 0, 176, 168, 212, 147, 40, 92, 45, 247, 50, 144, 223, 183, 230,
+			31, 135, 15, 23, 180, 24, 1, 25, 122, 20, 156, 169, 54, 84, 73, 158, 163, 218, 254,
+			176, 168, 212, 147, 40, 92, 45, 247, 50, 144, 223, 183, 230, 31, 135, 15, 23, 180, 24,
+			1, 25, 122, 20, 156, 169, 54, 84, 73, 158, 163, 218, 254, 0,
 		];
 		let signed_payload = tx.signed_payload().expect("test case works");
 		assert_eq!(signed_payload_encoded_expected.to_vec(), signed_payload.encode());
 	}
 
+	#[test]
+	fn tx_dynamic_signed_payload_matches_signed_payload() {
+		let tx = test_tx_instance();
+		let signed_extensions = crate::Chain::Kusama.signed_extensions();
+
+		let dynamic_payload =
+			tx.dynamic_signed_payload(&signed_extensions).expect("test case works");
+		let payload = tx.signed_payload().expect("test case works");
+		assert_eq!(dynamic_payload.encode(), payload.encode());
+
+		let dynamic_tx = tx
+			.dynamic_signed_tx_from_pair(&signed_extensions, AccountKeyring::Alice.pair())
+			.expect("test case works");
+		assert_eq!(
+			dynamic_tx.encode()[..37],
+			tx.signed_tx_from_pair(AccountKeyring::Alice.pair()).expect("test case works").encode()
+				[..37]
+		);
+		assert!(tx.dynamic_signed_payload(&["CheckAppId"]).is_err());
+	}
+
 	#[test]
 	fn tx_correctly_constructs_encoded_transaction_from_keyring_pair() {
 		let tx = test_tx_instance();
@@ -241,10 +650,10 @@ mod tests {
 		let signed_tx_encoded = signed_tx.encode().to_vec();
 
 		let version_and_address = [
-			33u8, 2, 132, 212, 53, 147, 199, 21, 253, 211, 28, 97, 20, 26, 189, 4, 169, 159, 214,
-			130, 44, 133, 88, 133, 76, 205, 227, 154, 86, 132, 231, 165, 109, 162, 125, 1,
+			45u8, 2, 132, 0, 212, 53, 147, 199, 21, 253, 211, 28, 97, 20, 26, 189, 4, 169, 159,
+			214, 130, 44, 133, 88, 133, 76, 205, 227, 154, 86, 132, 231, 165, 109, 162, 125, 1,
 		];
-		assert_eq!(version_and_address, signed_tx_encoded[0..36]);
+		assert_eq!(version_and_address, signed_tx_encoded[0..37]);
 
 		// Sig is non-deterministic so we do not assert equivalence
 		let _sig = [
@@ -254,14 +663,59 @@ mod tests {
 			48, 10, 243, 52, 80, 143, 1, 32,
 		];
 
-		let extra = [0, 0, 0];
-		assert_eq!(extra, signed_tx_encoded[100..103]);
+		let extra = [0, 0, 0, 0];
+		assert_eq!(extra, signed_tx_encoded[101..105]);
 
 		let call = [
-			5, 0, 142, 175, 4, 21, 22, 135, 115, 99, 38, 201, 254, 161, 126, 37, 252, 82, 135, 97,
-			54, 147, 201, 18, 144, 156, 178, 38, 170, 71, 148, 242, 106, 72, 48,
+			5, 0, 0, 142, 175, 4, 21, 22, 135, 115, 99, 38, 201, 254, 161, 126, 37, 252, 82, 135,
+			97, 54, 147, 201, 18, 144, 156, 178, 38, 170, 71, 148, 242, 106, 72, 48,
 		];
-		assert_eq!(call, signed_tx_encoded[103..]);
+		assert_eq!(call, signed_tx_encoded[105..]);
+	}
+
+	#[test]
+	fn tx_signing_bytes_hashes_long_payloads() {
+		let tx = test_tx_instance();
+		let payload = tx.signed_payload().expect("test case works");
+		let signing_bytes = tx.signing_bytes().expect("test case works");
+		assert!(!signing_bytes.hashed);
+		assert_eq!(signing_bytes.bytes, payload.encode());
+
+		// e.g. a batch of transfers
+		let mut config =
+			tx_config_with_call(GenericCall::new(CallIndex::new(1, 0), RawArgs(vec![7; 300])));
+		let tx: Tx<RawArgs, KusamaRuntime> = Tx::new(config.clone()).expect("test case works");
+		let (call, extra, additional_signed) =
+			tx.signed_payload().expect("test case works").deconstruct();
+		let encoded_payload = (call, extra, additional_signed).encode();
+		assert!(encoded_payload.len() > MAX_UNHASHED_PAYLOAD_LEN);
+
+		let signing_bytes = tx.signing_bytes().expect("test case works");
+		assert!(signing_bytes.hashed);
+		assert_eq!(signing_bytes.bytes, blake2_256(&encoded_payload).to_vec());
+		assert_eq!(signing_bytes.bytes, tx.signed_payload().expect("test case works").encode());
+
+		config.call = GenericCall::new(CallIndex::new(1, 0), RawArgs(vec![]));
+		let tx: Tx<RawArgs, KusamaRuntime> = Tx::new(config).expect("test case works");
+		assert!(!tx.signing_bytes().expect("test case works").hashed);
+	}
+
+	#[test]
+	fn tx_attach_signature_works() {
+		let pair = sp_keyring::Ed25519Keyring::Alice.pair();
+		for args in [vec![7; 10], vec![7; 300]] {
+			let mut config =
+				tx_config_with_call(GenericCall::new(CallIndex::new(1, 0), RawArgs(args)));
+			config.address = sp_keyring::Ed25519Keyring::Alice.to_account_id().into();
+			let tx: Tx<RawArgs, KusamaRuntime> = Tx::new(config).expect("test case works");
+
+			let signing_bytes = tx.signing_bytes().expect("test case works");
+			let signature = sp_runtime::MultiSignature::from(pair.sign(&signing_bytes.bytes));
+			assert_eq!(
+				tx.attach_signature(signature).expect("test case works"),
+				tx.signed_tx_from_pair(pair.clone()).expect("test case works")
+			);
+		}
 	}
 
 	#[test]
@@ -272,6 +726,6 @@ mod tests {
 			Transfer { to: AccountKeyring::Bob.to_account_id().into(), amount: 12 };
 		let transfer_call = GenericCall::new(CallIndex::new(5, 0), transfer_args);
 		assert_eq!(tx.call(), &transfer_call);
-		assert_eq!(tx.address(), &AccountKeyring::Alice.to_account_id());
+		assert_eq!(tx.address(), &AccountKeyring::Alice.to_account_id().into());
 	}
 }
@@ -0,0 +1,363 @@
+use super::{
+	extra::{Extra, SignedExtra},
+	GenericCall, SignedPayload, SigningBytes,
+};
+use crate::{
+	chain::check_genesis_hash,
+	frame::system::{AsAccountId, System},
+	runtimes::Runtime,
+	Error,
+};
+use codec::{Compact, Decode, Encode};
+use core::fmt::Debug;
+use sp_runtime::{
+	generic::Era,
+	traits::{IdentifyAccount, Verify},
+};
+
+/// Version of the extrinsic format this crate encodes.
+const EXTRINSIC_VERSION: u8 = 4;
+/// Bit of the version byte set for signed extrinsics.
+const SIGNED_BIT: u8 = 0b1000_0000;
+
+/// The additional signed data of a transaction, which is signed but not part of the extrinsic, so
+/// it has to be known to verify the signature of an extrinsic.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdditionalSignedData<R: System> {
+	/// API specification version of the runtime.
+	pub spec_version: u32,
+	/// Transaction version of the runtime.
+	pub tx_version: u32,
+	/// Hash of the network's genesis block.
+	pub genesis_hash: R::Hash,
+	/// Hash of the checkpoint block of a mortal transaction. Immortal transactions use the
+	/// genesis hash, which is also used if this is `None`.
+	pub checkpoint_hash: Option<R::Hash>,
+	/// RFC-78 metadata hash, if the runtime has `CheckMetadataHash` and the extrinsic enables it.
+	pub metadata_hash: Option<[u8; 32]>,
+}
+
+/// Outcome of each check of `verify_extrinsic`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ExtrinsicChecks {
+	/// The length prefix of the extrinsic matches its length.
+	pub length: bool,
+	/// The extrinsic is of the version this crate encodes.
+	pub version: bool,
+	/// The genesis hash is the one of the runtime's chain, if it is for a known `Chain`.
+	pub genesis_hash: bool,
+	/// The signer's address is an account, as needed to verify the signature.
+	pub signer_account: bool,
+	/// The signature is valid for the rebuilt `SignedPayload` and the signer's account.
+	pub signature: bool,
+}
+
+impl ExtrinsicChecks {
+	/// Whether all checks passed.
+	pub fn all_passed(&self) -> bool {
+		self.length && self.version && self.genesis_hash && self.signer_account && self.signature
+	}
+}
+
+/// Components of a signed extrinsic and the outcome of verifying it.
+#[derive(Clone, Debug)]
+pub struct ExtrinsicReport<C: Encode + Decode + Clone, R: Runtime> {
+	/// Version byte, `0x84` for a signed extrinsic of version 4.
+	pub version: u8,
+	/// Address of the signer.
+	pub signer: R::Address,
+	/// Signature over the `SignedPayload`.
+	pub signature: R::Signature,
+	/// The call.
+	pub call: GenericCall<C>,
+	/// Nonce of the signer.
+	pub nonce: R::Index,
+	/// Tip, used for transaction priority.
+	pub tip: R::Balance,
+	/// Mortality of the transaction.
+	pub era: Era,
+	/// Outcome of each check.
+	pub checks: ExtrinsicChecks,
+}
+
+/// Decode a signed `UncheckedExtrinsic` with the `Extra` of runtime `R` and verify its signature,
+/// rebuilding the `SignedPayload` with `SignedExtra::from_extra` from its extra and the given
+/// additional signed data.
+///
+/// Errors if the bytes can not be decoded as such an extrinsic, e.g. if it is unsigned, or if the
+/// checkpoint hash of an immortal transaction is not the genesis hash, or if a metadata hash is
+/// given but the extrinsic does not enable `CheckMetadataHash` or vice versa. The outcome of the
+/// checks of a decoded extrinsic is reported in `ExtrinsicReport::checks` instead.
+pub fn verify_extrinsic<C, R>(
+	bytes: &[u8],
+	additional_signed: &AdditionalSignedData<R>,
+) -> Result<ExtrinsicReport<C, R>, Error>
+where
+	C: Encode + Decode + Clone,
+	R: Runtime + Clone + Debug + Eq,
+	R::Address: AsAccountId<R::AccountId>,
+	<R::Signature as Verify>::Signer: IdentifyAccount<AccountId = R::AccountId>,
+{
+	let input = &mut &bytes[..];
+	Compact::<u32>::decode(input)?;
+	let version = u8::decode(input)?;
+	if version & SIGNED_BIT == 0 {
+		return Err("Extrinsic is not signed".into());
+	}
+	let signer = R::Address::decode(input)?;
+	let signature = R::Signature::decode(input)?;
+	let extra = Extra::<R>::decode(input)?;
+	let call = GenericCall::<C>::decode(input)?;
+	if !input.is_empty() {
+		return Err("Extrinsic has trailing bytes".into());
+	}
+
+	let signed_extra = R::Extra::from_extra(extra, additional_signed)?;
+	let payload = SignedPayload::<C, R>::new(call.clone(), signed_extra.extra())?.deconstruct();
+	let checks = check_extrinsic::<R>(
+		bytes,
+		&signer,
+		&signature,
+		&additional_signed.genesis_hash,
+		payload.encode(),
+	)?;
+	let default_extra = signed_extra.default_extra();
+
+	Ok(ExtrinsicReport {
+		version,
+		signer,
+		signature,
+		call,
+		nonce: default_extra.nonce,
+		tip: default_extra.tip,
+		era: default_extra.era_info.0,
+		checks,
+	})
+}
+
+/// Check a signed `UncheckedExtrinsic` of runtime `R` given as `bytes`, with its decoded signer and
+/// signature and its `SignedPayload` encoded from the call and extra of the extrinsic and the
+/// additional signed data with `genesis_hash`.
+///
+/// This is the verification of `verify_extrinsic`, for extrinsics whose extra is decoded otherwise,
+/// e.g. by the signed extensions listed in the runtime metadata.
+pub fn check_extrinsic<R>(
+	bytes: &[u8],
+	signer: &R::Address,
+	signature: &R::Signature,
+	genesis_hash: &R::Hash,
+	payload: Vec<u8>,
+) -> Result<ExtrinsicChecks, Error>
+where
+	R: Runtime,
+	R::Address: AsAccountId<R::AccountId>,
+	<R::Signature as Verify>::Signer: IdentifyAccount<AccountId = R::AccountId>,
+{
+	let input = &mut &bytes[..];
+	let length = Compact::<u32>::decode(input)?.0;
+	let length_matches = length as usize == input.len();
+	let version = u8::decode(input)?;
+
+	let account = signer.as_account_id();
+	let signing_bytes = SigningBytes::new(payload);
+
+	Ok(ExtrinsicChecks {
+		length: length_matches,
+		version: version & !SIGNED_BIT == EXTRINSIC_VERSION,
+		genesis_hash: check_genesis_hash::<R>(genesis_hash).is_ok(),
+		signer_account: account.is_some(),
+		signature: account
+			.is_some_and(|account| signature.verify(&signing_bytes.bytes[..], account)),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		balances::Transfer, CallIndex, KusamaRuntime, MortalConfig, Mortality, MultiAddress,
+		PolkadotRuntime, RawArgs, Tx, TxConfig,
+	};
+	use sp_core::H256;
+	use sp_keyring::AccountKeyring;
+
+	fn transfer_tx(mortality: Mortality<KusamaRuntime>) -> Tx<RawArgs, KusamaRuntime> {
+		let transfer = Transfer::<KusamaRuntime> {
+			to: AccountKeyring::Bob.to_account_id().into(),
+			amount: 12,
+		};
+
+		Tx::new(TxConfig {
+			call: GenericCall::new(CallIndex::new(4, 0), RawArgs(transfer.encode())),
+			address: AccountKeyring::Alice.to_account_id().into(),
+			nonce: 3,
+			tx_version: 4,
+			spec_version: 2026,
+			genesis_hash: KusamaRuntime::GENESIS_HASH,
+			mortality,
+			tip: 1_000,
+			fee_asset: None,
+			metadata_hash: None,
+		})
+		.expect("test case works")
+	}
+
+	fn additional_signed(checkpoint_hash: Option<H256>) -> AdditionalSignedData<KusamaRuntime> {
+		AdditionalSignedData {
+			spec_version: 2026,
+			tx_version: 4,
+			genesis_hash: KusamaRuntime::GENESIS_HASH,
+			checkpoint_hash,
+			metadata_hash: None,
+		}
+	}
+
+	#[test]
+	fn verify_extrinsic_works() {
+		let checkpoint_hash = H256::repeat_byte(1);
+		let tx = transfer_tx(Mortality::Mortal(MortalConfig {
+			period: 64,
+			checkpoint_block_hash: checkpoint_hash,
+			checkpoint_block_number: 100,
+		}));
+		let extrinsic =
+			tx.signed_tx_from_pair(AccountKeyring::Alice.pair()).expect("test case works").encode();
+
+		let report = verify_extrinsic::<RawArgs, KusamaRuntime>(
+			&extrinsic,
+			&additional_signed(Some(checkpoint_hash)),
+		)
+		.expect("test case works");
+		assert!(report.checks.all_passed());
+		assert_eq!(report.version, 0x84);
+		assert_eq!(&report.signer, tx.address());
+		assert_eq!(&report.call, tx.call());
+		assert_eq!((report.nonce, report.tip, report.era), (3, 1_000, Era::mortal(64, 100)));
+
+		// Other additional signed data than was signed
+		let report =
+			verify_extrinsic::<RawArgs, KusamaRuntime>(&extrinsic, &additional_signed(None))
+				.expect("test case works");
+		assert!(!report.checks.signature);
+		assert!(report.checks.genesis_hash && report.checks.length);
+		let mut other_version = additional_signed(Some(checkpoint_hash));
+		other_version.spec_version = 2027;
+		let report = verify_extrinsic::<RawArgs, KusamaRuntime>(&extrinsic, &other_version)
+			.expect("test case works");
+		assert!(!report.checks.signature);
+	}
+
+	#[test]
+	fn verify_extrinsic_rebuilds_additional_signed_data_with_the_extra_of_the_runtime() {
+		let tx: Tx<RawArgs, PolkadotRuntime> = Tx::new(TxConfig {
+			call: GenericCall::new(CallIndex::new(5, 3), RawArgs(vec![])),
+			address: AccountKeyring::Alice.to_account_id().into(),
+			nonce: 3,
+			tx_version: 26,
+			spec_version: 1_003_000,
+			genesis_hash: PolkadotRuntime::GENESIS_HASH,
+			mortality: Mortality::Immortal,
+			tip: 0,
+			fee_asset: None,
+			metadata_hash: Some([2; 32]),
+		})
+		.expect("test case works");
+		let extrinsic =
+			tx.signed_tx_from_pair(AccountKeyring::Alice.pair()).expect("test case works").encode();
+		let mut additional_signed = AdditionalSignedData {
+			spec_version: 1_003_000,
+			tx_version: 26,
+			genesis_hash: PolkadotRuntime::GENESIS_HASH,
+			checkpoint_hash: None,
+			metadata_hash: Some([2; 32]),
+		};
+
+		let report = verify_extrinsic::<RawArgs, PolkadotRuntime>(&extrinsic, &additional_signed)
+			.expect("test case works");
+		assert!(report.checks.all_passed());
+
+		// The extrinsic enables `CheckMetadataHash`, so its metadata hash is needed
+		additional_signed.metadata_hash = None;
+		assert!(
+			verify_extrinsic::<RawArgs, PolkadotRuntime>(&extrinsic, &additional_signed).is_err()
+		);
+		additional_signed.metadata_hash = Some([3; 32]);
+		let report = verify_extrinsic::<RawArgs, PolkadotRuntime>(&extrinsic, &additional_signed)
+			.expect("test case works");
+		assert!(report.checks.genesis_hash && !report.checks.signature);
+	}
+
+	#[test]
+	fn verify_extrinsic_reports_failed_checks() {
+		let tx = transfer_tx(Mortality::Immortal);
+		let extrinsic =
+			tx.signed_tx_from_pair(AccountKeyring::Alice.pair()).expect("test case works").encode();
+		assert!(verify_extrinsic::<RawArgs, KusamaRuntime>(&extrinsic, &additional_signed(None))
+			.expect("test case works")
+			.checks
+			.all_passed());
+
+		// Wrong length prefix
+		let mut wrong_length = extrinsic.clone();
+		wrong_length[0] += 4;
+		let checks =
+			verify_extrinsic::<RawArgs, KusamaRuntime>(&wrong_length, &additional_signed(None))
+				.expect("test case works")
+				.checks;
+		assert!(!checks.length && checks.signature);
+
+		// Signed by Alice, claiming to be Bob
+		let mut wrong_signer = extrinsic.clone();
+		let bob = AccountKeyring::Bob.to_account_id();
+		wrong_signer[4..36].copy_from_slice(bob.as_ref());
+		let checks =
+			verify_extrinsic::<RawArgs, KusamaRuntime>(&wrong_signer, &additional_signed(None))
+				.expect("test case works")
+				.checks;
+		assert!(checks.signer_account && !checks.signature);
+
+		// Signer given by index
+		let mut body = vec![extrinsic[2]];
+		MultiAddress::<sp_runtime::AccountId32, u32>::Index(1).encode_to(&mut body);
+		body.extend(&extrinsic[36..]);
+		let index_signer = [Compact(body.len() as u32).encode(), body].concat();
+		let checks =
+			verify_extrinsic::<RawArgs, KusamaRuntime>(&index_signer, &additional_signed(None))
+				.expect("test case works")
+				.checks;
+		assert!(checks.length && !checks.signer_account && !checks.signature);
+
+		// Genesis hash of another chain
+		let mut polkadot = additional_signed(None);
+		polkadot.genesis_hash = crate::PolkadotRuntime::GENESIS_HASH;
+		let checks = verify_extrinsic::<RawArgs, KusamaRuntime>(&extrinsic, &polkadot)
+			.expect("test case works")
+			.checks;
+		assert!(!checks.genesis_hash && !checks.signature);
+	}
+
+	#[test]
+	fn verify_extrinsic_rejects_invalid_extrinsics() {
+		let tx = transfer_tx(Mortality::Immortal);
+		let extrinsic =
+			tx.signed_tx_from_pair(AccountKeyring::Alice.pair()).expect("test case works").encode();
+
+		// Unsigned
+		let mut unsigned = extrinsic.clone();
+		unsigned[2] = 0x04;
+		assert!(verify_extrinsic::<RawArgs, KusamaRuntime>(&unsigned, &additional_signed(None))
+			.is_err());
+		// Truncated
+		assert!(verify_extrinsic::<Transfer<KusamaRuntime>, KusamaRuntime>(
+			&extrinsic[..extrinsic.len() - 1],
+			&additional_signed(None)
+		)
+		.is_err());
+		// Immortal with a checkpoint hash other than the genesis hash
+		assert!(verify_extrinsic::<RawArgs, KusamaRuntime>(
+			&extrinsic,
+			&additional_signed(Some(H256::repeat_byte(1)))
+		)
+		.is_err());
+	}
+}
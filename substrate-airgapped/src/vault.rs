@@ -0,0 +1,307 @@
+//! Payloads for signing with Polkadot Vault (formerly Parity Signer), which are shown to the
+//! device as QR codes, and the signatures it returns.
+
+use crate::{
+	frame::{balances::Balances, system::System},
+	runtimes::Runtime,
+	Error, Tx,
+};
+use codec::{Decode, Encode};
+use core::convert::TryFrom;
+use sp_core::{ecdsa, ed25519, sr25519, Pair, H256};
+
+/// First byte of payloads for substrate based chains.
+const SUBSTRATE_PREFIX: u8 = 0x53;
+/// Action byte of a payload to sign a transaction.
+const SIGN_TRANSACTION: u8 = 0x02;
+/// Action byte of a payload to load metadata.
+const LOAD_METADATA: u8 = 0x80;
+/// Crypto byte of a payload that is not signed by a verifier.
+const UNSIGNED: u8 = 0xff;
+/// First byte of each frame of multi-frame QR data.
+const MULTIPART_PREFIX: u8 = 0x00;
+
+/// Maximum number of payload bytes in each frame of multi-frame QR data, as used by Polkadot
+/// Vault companion apps.
+pub const DEFAULT_FRAME_SIZE: usize = 1024;
+
+/// Crypto of the account signing with Polkadot Vault.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Crypto {
+	/// Ed25519 keys.
+	Ed25519,
+	/// Sr25519 keys.
+	Sr25519,
+	/// Ecdsa keys.
+	Ecdsa,
+}
+
+impl Crypto {
+	/// Byte identifying the crypto in payloads.
+	pub fn byte(&self) -> u8 {
+		match self {
+			Crypto::Ed25519 => 0x00,
+			Crypto::Sr25519 => 0x01,
+			Crypto::Ecdsa => 0x02,
+		}
+	}
+
+	/// Length of a public key of the crypto.
+	pub fn public_key_len(&self) -> usize {
+		match self {
+			Crypto::Ed25519 | Crypto::Sr25519 => 32,
+			Crypto::Ecdsa => 33,
+		}
+	}
+}
+
+/// Key of a verifier signing payloads to update a network on the device. Once it accepted a payload
+/// signed by a verifier, the device only accepts updates of the network signed by the same key.
+#[derive(Clone, Copy)]
+pub enum Verifier<'a> {
+	/// Ed25519 key.
+	Ed25519(&'a ed25519::Pair),
+	/// Sr25519 key.
+	Sr25519(&'a sr25519::Pair),
+	/// Ecdsa key.
+	Ecdsa(&'a ecdsa::Pair),
+}
+
+impl Verifier<'_> {
+	/// Crypto of the key.
+	pub fn crypto(&self) -> Crypto {
+		match self {
+			Verifier::Ed25519(_) => Crypto::Ed25519,
+			Verifier::Sr25519(_) => Crypto::Sr25519,
+			Verifier::Ecdsa(_) => Crypto::Ecdsa,
+		}
+	}
+
+	/// Public key.
+	pub fn public_key(&self) -> Vec<u8> {
+		match self {
+			Verifier::Ed25519(pair) => pair.public().0.to_vec(),
+			Verifier::Sr25519(pair) => pair.public().0.to_vec(),
+			Verifier::Ecdsa(pair) => pair.public().as_ref().to_vec(),
+		}
+	}
+
+	/// Sign the content of a payload.
+	pub fn sign(&self, content: &[u8]) -> Vec<u8> {
+		match self {
+			Verifier::Ed25519(pair) => pair.sign(content).0.to_vec(),
+			Verifier::Sr25519(pair) => pair.sign(content).0.to_vec(),
+			Verifier::Ecdsa(pair) => AsRef::<[u8]>::as_ref(&pair.sign(content)).to_vec(),
+		}
+	}
+}
+
+/// Encode the payload to sign a transaction with Polkadot Vault: the prefix `0x53`, the crypto
+/// and action byte, the signer's public key, the `SignedPayload` with the length prefixed call and
+/// the genesis hash.
+///
+/// The payload is not hashed if it is longer than 256 bytes, as the device shows the call before
+/// signing its hash. Errors if the public key does not have the length of the crypto's keys.
+pub fn transaction_payload<C, R>(
+	tx: &Tx<C, R>,
+	crypto: Crypto,
+	public_key: &[u8],
+) -> Result<Vec<u8>, Error>
+where
+	C: Encode + Decode + Clone,
+	R: System + Balances + Runtime,
+{
+	if public_key.len() != crypto.public_key_len() {
+		return Err("Public key does not have the length of the crypto's keys".into());
+	}
+	let (call, extra, additional_signed) = tx.signed_payload()?.deconstruct();
+
+	let mut payload = vec![SUBSTRATE_PREFIX, crypto.byte(), SIGN_TRANSACTION];
+	payload.extend_from_slice(public_key);
+	call.encode().encode_to(&mut payload);
+	(extra, additional_signed).encode_to(&mut payload);
+	tx.genesis_hash().encode_to(&mut payload);
+
+	Ok(payload)
+}
+
+/// Encode the payload to load metadata on the device: the prefix `0x53`, the crypto byte of the
+/// verifier (or `0xff` if unsigned) and action byte, the verifier's public key, the content (the
+/// length prefixed bytes of the SCALE encoded `RuntimeMetadataPrefixed` and the genesis hash of
+/// the network) and the verifier's signature of the content.
+///
+/// The device reads the name and spec version of the runtime from the `Version` constant of the
+/// `System` pallet in the metadata.
+pub fn load_metadata_payload(
+	metadata: &[u8],
+	genesis_hash: H256,
+	verifier: Option<Verifier>,
+) -> Vec<u8> {
+	let content = (metadata, genesis_hash).encode();
+
+	match verifier {
+		Some(verifier) => {
+			let mut payload = vec![SUBSTRATE_PREFIX, verifier.crypto().byte(), LOAD_METADATA];
+			payload.extend(verifier.public_key());
+			payload.extend(&content);
+			payload.extend(verifier.sign(&content));
+			payload
+		}
+		None => [&[SUBSTRATE_PREFIX, UNSIGNED, LOAD_METADATA][..], &content].concat(),
+	}
+}
+
+/// Split QR data into frames of at most `frame_size` bytes of data for multi-frame QR codes. Each
+/// frame is prefixed with `0x00`, then the number of frames and the index of the frame as big
+/// endian `u16`.
+///
+/// Errors if `frame_size` is zero or the data needs more than `u16::MAX` frames.
+pub fn frames(data: &[u8], frame_size: usize) -> Result<Vec<Vec<u8>>, Error> {
+	if frame_size == 0 {
+		return Err("Frame size must not be zero".into());
+	}
+	let chunks: Vec<_> = data.chunks(frame_size).collect();
+	let frame_count = u16::try_from(chunks.len().max(1)).map_err(|_| "Too many frames")?;
+
+	let frames = if chunks.is_empty() { vec![&[][..]] } else { chunks }
+		.into_iter()
+		.enumerate()
+		.map(|(index, chunk)| {
+			let mut frame = vec![MULTIPART_PREFIX];
+			frame.extend_from_slice(&frame_count.to_be_bytes());
+			frame.extend_from_slice(&(index as u16).to_be_bytes());
+			frame.extend_from_slice(chunk);
+			frame
+		})
+		.collect();
+
+	Ok(frames)
+}
+
+/// Parse the signature QR code shown by Polkadot Vault, the hex of the SCALE encoded signature
+/// (e.g. `0x01` followed by the signature of an sr25519 key for a `MultiSignature`), into the
+/// signature of runtime `R`, to create the signed transaction with `tx_from_parts`.
+#[cfg(feature = "std")]
+pub fn signature_from_qr<R: Runtime>(data: &str) -> Result<R::Signature, Error> {
+	crate::signer::decode_signature::<R>(data)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		tx_from_parts, CallIndex, GenericCall, KusamaRuntime, Mortality, RawArgs, SigningBytes,
+		TxConfig,
+	};
+	use sp_keyring::AccountKeyring;
+	use sp_runtime::{traits::Verify, MultiSignature};
+
+	fn remark_tx(len: usize) -> Tx<RawArgs, KusamaRuntime> {
+		Tx::new(TxConfig {
+			call: GenericCall::new(CallIndex::new(0, 1), RawArgs(vec![7; len].encode())),
+			address: AccountKeyring::Alice.to_account_id().into(),
+			nonce: 5,
+			tx_version: 4,
+			spec_version: 2026,
+			genesis_hash: KusamaRuntime::GENESIS_HASH,
+			mortality: Mortality::Immortal,
+			tip: 0,
+			fee_asset: None,
+			metadata_hash: None,
+		})
+		.expect("test case works")
+	}
+
+	#[test]
+	fn transaction_payload_works() {
+		let public = AccountKeyring::Alice.public();
+		for len in [10, 300] {
+			let tx = remark_tx(len);
+			let payload = transaction_payload(&tx, Crypto::Sr25519, public.as_ref())
+				.expect("test case works");
+
+			assert_eq!(payload[..3], [0x53, 0x01, 0x02]);
+			assert_eq!(payload[3..35], public.0);
+			assert_eq!(payload[payload.len() - 32..], KusamaRuntime::GENESIS_HASH.0);
+
+			// The device signs the `SignedPayload`, without the length prefix of the call
+			let input = &mut &payload[35..payload.len() - 32];
+			let call = Vec::<u8>::decode(input).expect("test case works");
+			assert_eq!(call, tx.call().encode());
+			assert_eq!(
+				SigningBytes::new([&call[..], input].concat()),
+				tx.signing_bytes().expect("test case works")
+			);
+		}
+
+		assert!(transaction_payload(&remark_tx(10), Crypto::Ecdsa, public.as_ref()).is_err());
+	}
+
+	#[test]
+	fn load_metadata_payload_works() {
+		let metadata = b"meta\x0e".to_vec();
+		let genesis_hash = KusamaRuntime::GENESIS_HASH;
+		let content = [&[20][..], &metadata, genesis_hash.as_bytes()].concat();
+
+		assert_eq!(
+			load_metadata_payload(&metadata, genesis_hash, None),
+			[&[0x53, 0xff, 0x80][..], &content].concat()
+		);
+
+		let pair = sr25519::Pair::from_string("//Alice", None).expect("test case works");
+		let payload =
+			load_metadata_payload(&metadata, genesis_hash, Some(Verifier::Sr25519(&pair)));
+		assert_eq!(payload[..3], [0x53, 0x01, 0x80]);
+		assert_eq!(payload[3..35], pair.public().0);
+		assert_eq!(payload[35..35 + content.len()], content[..]);
+		let signature = sr25519::Signature::from_slice(&payload[35 + content.len()..]);
+		assert!(sr25519::Pair::verify(&signature, &content, &pair.public()));
+
+		let pair = ecdsa::Pair::from_string("//Alice", None).expect("test case works");
+		let payload = load_metadata_payload(&metadata, genesis_hash, Some(Verifier::Ecdsa(&pair)));
+		assert_eq!(payload[..3], [0x53, 0x02, 0x80]);
+		assert_eq!(payload.len(), 3 + 33 + content.len() + 65);
+	}
+
+	#[test]
+	fn frames_works() {
+		let data: Vec<u8> = (0..=255).cycle().take(2500).collect();
+		let qr_frames = frames(&data, DEFAULT_FRAME_SIZE).expect("test case works");
+
+		assert_eq!(qr_frames.len(), 3);
+		for (index, frame) in qr_frames.iter().enumerate() {
+			assert_eq!(frame[..5], [0x00, 0x00, 0x03, 0x00, index as u8]);
+		}
+		assert_eq!(qr_frames[2].len(), 5 + 2500 - 2 * DEFAULT_FRAME_SIZE);
+		let joined: Vec<u8> = qr_frames.iter().flat_map(|frame| frame[5..].to_vec()).collect();
+		assert_eq!(joined, data);
+
+		assert_eq!(
+			frames(&[1, 2], DEFAULT_FRAME_SIZE),
+			Ok(vec![vec![0x00, 0x00, 0x01, 0x00, 0x00, 1, 2]])
+		);
+		assert!(frames(&data, 0).is_err());
+		assert!(frames(&[0; 70_000], 1).is_err());
+	}
+
+	#[test]
+	fn signature_from_qr_works() {
+		let tx = remark_tx(300);
+		let pair = sr25519::Pair::from_string("//Alice", None).expect("test case works");
+		let signing_bytes = tx.signing_bytes().expect("test case works");
+		let signature = MultiSignature::from(pair.sign(&signing_bytes.bytes));
+		let qr = format!("0x{}", hex::encode(signature.encode()));
+
+		let parsed = signature_from_qr::<KusamaRuntime>(&qr).expect("test case works");
+		assert!(parsed.verify(&signing_bytes.bytes[..], &AccountKeyring::Alice.to_account_id()));
+		let signed = tx_from_parts::<RawArgs, KusamaRuntime>(
+			tx.address().clone(),
+			parsed,
+			tx.signed_payload().expect("test case works"),
+		);
+		assert_eq!(signed.signature.map(|(_, signature, _)| signature), Some(signature));
+
+		assert!(signature_from_qr::<KusamaRuntime>("0x0102").is_err());
+		assert!(signature_from_qr::<KusamaRuntime>("not hex").is_err());
+	}
+}